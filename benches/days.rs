@@ -0,0 +1,78 @@
+use advent_of_code_2020::{runner, synth};
+use advent_of_code_2020::{Registry, Runner};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::fs;
+use std::time::Duration;
+
+/// total time spent in the generator while running `runner` `iterations` times.
+fn parse_time(runner: &dyn Runner, input: &str, iterations: u64) -> Duration {
+    (0..iterations)
+        .map(|_| runner.run(input).expect("invalid input").parse_time)
+        .sum()
+}
+
+/// total time spent in the solver while running `runner` `iterations` times.
+fn solve_time(runner: &dyn Runner, input: &str, iterations: u64) -> Duration {
+    (0..iterations)
+        .map(|_| runner.run(input).expect("invalid input").solve_time)
+        .sum()
+}
+
+/// benchmarks every part on the puzzle input.
+fn puzzle_inputs(c: &mut Criterion) {
+    let registry = Registry::default();
+
+    for runner in registry.runners() {
+        let input = fs::read_to_string(runner::default_input_path(runner.day())).unwrap();
+        let mut group = c.benchmark_group(format!("day{}/part{}", runner.day(), runner.part()));
+
+        if runner.day() == 11 {
+            group.sample_size(10);
+        }
+
+        group.bench_function("parse", |b| {
+            b.iter_custom(|iterations| parse_time(runner, &input, iterations))
+        });
+        group.bench_function("solve", |b| {
+            b.iter_custom(|iterations| solve_time(runner, &input, iterations))
+        });
+        group.finish();
+    }
+}
+
+/// benchmarks the solvers on synthetic inputs of increasing size to show how they scale.
+fn synthetic_inputs(c: &mut Criterion) {
+    let registry = Registry::default();
+    let sizes: Vec<(u32, Vec<usize>)> = vec![
+        (7, vec![50, 500, 5_000]),
+        (8, vec![100, 1_000, 10_000]),
+        (9, vec![100, 500, 1_000]),
+        (11, vec![16, 32, 64, 1_000]),
+    ];
+
+    for (day, sizes) in sizes {
+        let inputs: Vec<(usize, String)> = sizes
+            .into_iter()
+            .map(|size| (size, synth::generate(day, 0, size).unwrap().input))
+            .collect();
+
+        for runner in registry.day(day) {
+            let mut group = c.benchmark_group(format!(
+                "day{}/part{}/synthetic",
+                runner.day(),
+                runner.part()
+            ));
+            group.sample_size(10);
+
+            for (size, input) in &inputs {
+                group.bench_with_input(BenchmarkId::new("solve", size), input, |b, input| {
+                    b.iter_custom(|iterations| solve_time(runner, input, iterations))
+                });
+            }
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, puzzle_inputs, synthetic_inputs);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+//! Time and memory spent in the generators of the days with text heavy inputs.
+//!
+//! Besides the criterion timings, every benchmark prints the number of allocations and the bytes
+//! allocated by a single run of the generator, counted by a wrapper around the system allocator.
+
+use advent_of_code_2020::{runner, synth};
+use advent_of_code_2020::{Registry, Runner};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocator counting the allocations and the allocated bytes.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size.saturating_sub(layout.size()), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// days whose generators copy a lot of text, with the sizes of their synthetic inputs
+const DAYS: [(u32, [usize; 3]); 3] = [
+    (2, [100, 1_000, 10_000]),
+    (4, [100, 1_000, 10_000]),
+    (7, [50, 500, 5_000]),
+];
+
+/// prints the allocations of a single run of the generator of `runner`.
+fn report_allocations(name: &str, runner: &dyn Runner, input: &str) {
+    let (allocations, bytes) = (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        BYTES.load(Ordering::Relaxed),
+    );
+    runner.parse(input).expect("invalid input");
+
+    println!(
+        "{}: {} allocations, {} bytes allocated",
+        name,
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        BYTES.load(Ordering::Relaxed) - bytes
+    );
+}
+
+fn generators(c: &mut Criterion) {
+    let registry = Registry::default();
+
+    for (day, sizes) in DAYS.iter() {
+        // both parts share the generator
+        let runner = registry.get(*day, 1).unwrap();
+        let mut inputs: Vec<(String, String)> = sizes
+            .iter()
+            .map(|size| {
+                let input = synth::generate(*day, 0, *size).unwrap().input;
+                (format!("synthetic/{}", size), input)
+            })
+            .collect();
+        if let Ok(input) = fs::read_to_string(runner::default_input_path(*day)) {
+            inputs.push(("puzzle".to_string(), input));
+        }
+
+        let mut group = c.benchmark_group(format!("day{}/generator", day));
+        for (name, input) in &inputs {
+            report_allocations(&format!("day{}/generator/{}", day, name), runner, input);
+
+            group.bench_with_input(BenchmarkId::from_parameter(name), input, |b, input| {
+                b.iter(|| runner.parse(input).expect("invalid input"))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, generators);
+criterion_main!(benches);
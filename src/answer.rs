@@ -0,0 +1,175 @@
+//! Answers of the solutions, independent of the type each day computes them as.
+
+use std::convert::{Infallible, TryFrom};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Answer of a single part.
+///
+/// Integers are equal regardless of their variant, so the answer parsed from a file equals the
+/// computed one.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Integer(i64),
+    /// integers which don't fit into an `i64`
+    BigInteger(i128),
+    Text(String),
+    /// rendered output spanning several lines, e.g. letters drawn with `#`
+    Render(String),
+}
+
+impl Answer {
+    /// the answer as an integer, if it is one.
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Answer::Integer(n) => Some(i128::from(*n)),
+            Answer::BigInteger(n) => Some(*n),
+            Answer::Text(_) | Answer::Render(_) => None,
+        }
+    }
+
+    pub fn is_render(&self) -> bool {
+        matches!(self, Answer::Render(_))
+    }
+
+    /// the answer on a single line, with the line breaks of renders written as `\n`.
+    /// [`from_str`](Answer::from_str) turns it into the same answer again.
+    pub fn to_line(&self) -> String {
+        match self {
+            Answer::Render(render) => render.replace('\n', "\\n"),
+            answer => answer.to_string(),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) | (Answer::Render(a), Answer::Render(b)) => a == b,
+            (a, b) => a.as_integer().is_some() && a.as_integer() == b.as_integer(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => Display::fmt(n, f),
+            Answer::BigInteger(n) => Display::fmt(n, f),
+            Answer::Text(text) | Answer::Render(text) => f.pad(text),
+        }
+    }
+}
+
+/// parses integers as integers, text containing line breaks (or `\n`, as written by
+/// [`to_line`](Answer::to_line)) as render and anything else as text.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(n) = s.parse::<i128>() {
+            return Ok(Answer::from(n));
+        }
+
+        Ok(match s.contains('\n') || s.contains("\\n") {
+            true => Answer::Render(s.replace("\\n", "\n")),
+            false => Answer::Text(s.to_string()),
+        })
+    }
+}
+
+macro_rules! from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(n: $integer) -> Self {
+                    match i64::try_from(n) {
+                        Ok(n) => Answer::Integer(n),
+                        Err(_) => Answer::BigInteger(n as i128),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        match i128::try_from(n) {
+            Ok(n) => Answer::from(n),
+            Err(_) => Answer::Text(n.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::*;
+
+    #[test]
+    fn test_integers_are_equal_across_variants() {
+        assert_eq!(Answer::Integer(7), Answer::BigInteger(7));
+        assert_eq!(Answer::from(7u64), Answer::from(7i32));
+        assert_eq!(Answer::BigInteger(i128::MAX), Answer::from(i128::MAX));
+        assert_ne!(Answer::Integer(7), Answer::Text("7".to_string()));
+        assert_ne!(
+            Answer::Text("#".to_string()),
+            Answer::Render("#".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_integer() {
+        assert_eq!("Integer(-3)", format!("{:?}", Answer::from(-3i8)));
+        assert_eq!(
+            "BigInteger(18446744073709551615)",
+            format!("{:?}", Answer::from(u64::MAX))
+        );
+        assert_eq!(
+            "Text(\"340282366920938463463374607431768211455\")",
+            format!("{:?}", Answer::from(u128::MAX))
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("  42", format!("{:>4}", Answer::from(42u32)));
+        assert_eq!("ab  |", format!("{:<4}|", Answer::from("ab")));
+        assert_eq!("#.\n.#", Answer::Render("#.\n.#".to_string()).to_string());
+    }
+
+    #[test]
+    fn test_line_round_trip() {
+        let answers = [
+            Answer::from(12),
+            Answer::from(u64::MAX),
+            Answer::from("ABCDEFGH"),
+            Answer::Render("#..#\n####\n#..#".to_string()),
+        ];
+
+        for answer in answers {
+            assert!(!answer.to_line().contains('\n'));
+            assert_eq!(answer, answer.to_line().parse().unwrap());
+            assert_eq!(
+                format!("{:?}", answer),
+                format!("{:?}", answer.to_line().parse::<Answer>().unwrap())
+            );
+        }
+    }
+}
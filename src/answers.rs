@@ -0,0 +1,264 @@
+//! Registry of known answers, used to catch changes of the answers while refactoring.
+//!
+//! The answers are stored in a plain text file with one answer per line:
+//!
+//! ```text
+//! # year day part fingerprint answer
+//! 2020 1 1 495f0abc2bb550dc 926464
+//! ```
+//!
+//! The fingerprint identifies the input the answer belongs to, so answers for inputs of several
+//! accounts can live next to each other. Rendered answers are written on a single line, see
+//! [`Answer::to_line`].
+
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::runner::YEAR;
+use crate::solution::Runner;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// location of the checked-in answers
+pub const DEFAULT_PATH: &str = "answers.txt";
+
+const FORMAT: &str = "<year> <day> <part> <fingerprint> <answer>";
+
+/// Hash identifying a puzzle input, ignoring trailing whitespace.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    /// FNV-1a hash of the [normalized](parse::normalize) input. Unlike `DefaultHasher` it is
+    /// stable across rust versions, which matters for the checked-in answers.
+    pub fn of(input: &str) -> Self {
+        let hash = parse::normalize(input)
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+
+        Fingerprint(hash)
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Key {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub fingerprint: Fingerprint,
+}
+
+impl Key {
+    /// key of the answer of `runner` for `input`.
+    pub fn of(runner: &dyn Runner, input: &str) -> Self {
+        Key {
+            year: YEAR,
+            day: runner.day(),
+            part: runner.part(),
+            fingerprint: Fingerprint::of(input),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: BTreeMap<Key, Answer>,
+}
+
+impl Answers {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(&path)?;
+
+        content.parse().map_err(|message| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.as_ref().display(), message),
+            )
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&Answer> {
+        self.answers.get(key)
+    }
+
+    pub fn insert(&mut self, key: Key, answer: Answer) {
+        self.answers.insert(key, answer);
+    }
+
+    /// compares `answer` with the known answer of `key`.
+    pub fn judge(&self, key: &Key, answer: &Answer) -> Verdict {
+        match self.get(key) {
+            Some(expected) if expected == answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.clone(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || format!("line {}: expected `{}`", index + 1, FORMAT);
+            let mut fields = line.splitn(5, ' ');
+            let mut number = || {
+                fields
+                    .next()
+                    .and_then(|field| field.parse().ok())
+                    .ok_or_else(invalid)
+            };
+
+            let (year, day, part) = (number()?, number()?, number()?);
+            let fingerprint = fields
+                .next()
+                .and_then(|field| u64::from_str_radix(field, 16).ok())
+                .ok_or_else(invalid)?;
+            let answer = fields.next().ok_or_else(invalid)?;
+            let Ok(answer) = answer.trim().parse();
+
+            let key = Key {
+                year,
+                day,
+                part,
+                fingerprint: Fingerprint(fingerprint),
+            };
+            answers.insert(key, answer);
+        }
+
+        Ok(answers)
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# year day part fingerprint answer")?;
+
+        for (key, answer) in &self.answers {
+            writeln!(
+                f,
+                "{} {} {} {} {}",
+                key.year,
+                key.day,
+                key.part,
+                key.fingerprint,
+                answer.to_line()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Wrong {
+        expected: Answer,
+    },
+    /// there is no known answer for this input
+    Unknown,
+}
+
+/// Outcome of solving a puzzle and comparing the answer with the known one.
+#[derive(Debug)]
+pub struct Verification {
+    pub key: Key,
+    pub result: Result<(Answer, Verdict), ParseError>,
+}
+
+impl Verification {
+    pub fn is_correct(&self) -> bool {
+        matches!(self.result, Ok((_, Verdict::Correct)))
+    }
+}
+
+/// solves the part of `runner` using `input` and compares the answer with the registered one.
+pub fn verify(answers: &Answers, runner: &dyn Runner, input: &str) -> Verification {
+    let key = Key::of(runner, input);
+    let result = runner.solve(input).map(|answer| {
+        let verdict = answers.judge(&key, &answer);
+        (answer, verdict)
+    });
+
+    Verification { key, result }
+}
+
+impl Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {:>2} part {}: ", self.key.day, self.key.part)?;
+
+        match &self.result {
+            Ok((answer, Verdict::Correct)) => write!(f, "ok       {}", answer.to_line()),
+            Ok((answer, Verdict::Wrong { expected })) => write!(
+                f,
+                "WRONG    expected {}, got {}",
+                expected.to_line(),
+                answer.to_line()
+            ),
+            Ok((answer, Verdict::Unknown)) => write!(f, "unknown  {}", answer.to_line()),
+            Err(e) => write!(f, "FAILED   {}", e.message()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answers::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut answers = Answers::default();
+        let key = Key {
+            year: 2020,
+            day: 1,
+            part: 2,
+            fingerprint: Fingerprint::of("1\n2\n"),
+        };
+        let render = Key { part: 1, ..key };
+        answers.insert(key, Answer::from(42));
+        answers.insert(render, Answer::Render("#.\n.#".to_string()));
+
+        let parsed: Answers = answers.to_string().parse().unwrap();
+
+        assert_eq!(Some(&Answer::from(42)), parsed.get(&key));
+        assert_eq!(
+            Some(&Answer::Render("#.\n.#".to_string())),
+            parsed.get(&render)
+        );
+        assert_eq!(
+            Verdict::Correct,
+            parsed.judge(&key, &Answer::BigInteger(42))
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_trailing_whitespace() {
+        assert_eq!(Fingerprint::of("1\n2"), Fingerprint::of("1\n2\n\n"));
+        assert_eq!(Fingerprint::of("1\n2"), Fingerprint::of("1 \r\n2\r\n"));
+        assert_ne!(Fingerprint::of("1\n2"), Fingerprint::of("2\n1"));
+    }
+}
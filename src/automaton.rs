@@ -0,0 +1,584 @@
+//! Cellular automata on a [`Grid`], like the seat layout of day 11.
+//!
+//! An [`Automaton`] is made of cells, a [`Neighbourhood`] deciding which cells a cell looks at and
+//! a [`Rule`] computing the next state of a cell from the number of its alive neighbours. Rules
+//! of the Life-like family are written as `B<counts>/S<counts>`, e.g. `B3/S23` for Conway's Game of
+//! Life or `B0/S0123` for the seats of day 11.
+//!
+//! The neighbours of every cell are looked up once, a generation only counts the alive ones and
+//! writes the next states into a second buffer which is swapped with the grid. [`Automaton::run`]
+//! recognizes generations it has seen before, so automata which never become stable end with an
+//! [`Outcome`] instead of running forever.
+
+use crate::cancel;
+use crate::grid::{Grid, Position, DIRECTIONS8};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// State of a single cell.
+pub trait Cell: Copy + Eq + Hash {
+    fn is_alive(self) -> bool;
+
+    /// the cell alive or dead. Cells which can't live, like the floor of the ferry, stay the same.
+    fn with_alive(self, alive: bool) -> Self;
+
+    /// whether the cell never changes, like the floor of the ferry. Static cells aren't simulated
+    /// and aren't neighbours of other cells.
+    fn is_static(self) -> bool {
+        false
+    }
+}
+
+impl Cell for bool {
+    fn is_alive(self) -> bool {
+        self
+    }
+
+    fn with_alive(self, alive: bool) -> Self {
+        alive
+    }
+}
+
+/// Cells a cell looks at.
+pub trait Neighbourhood<C> {
+    /// positions of the neighbours of the cell at `position`. Static cells are skipped anyway.
+    fn neighbours<'a>(
+        &'a self,
+        grid: &'a Grid<C>,
+        position: Position,
+    ) -> impl Iterator<Item = Position> + 'a;
+}
+
+/// The 4 cells sharing an edge with the cell.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Orthogonal;
+
+impl<C> Neighbourhood<C> for Orthogonal {
+    fn neighbours<'a>(
+        &'a self,
+        grid: &'a Grid<C>,
+        position: Position,
+    ) -> impl Iterator<Item = Position> + 'a {
+        grid.neighbours4(position).map(|(position, _)| position)
+    }
+}
+
+/// The 8 cells sharing an edge or a corner with the cell.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Adjacent;
+
+impl<C> Neighbourhood<C> for Adjacent {
+    fn neighbours<'a>(
+        &'a self,
+        grid: &'a Grid<C>,
+        position: Position,
+    ) -> impl Iterator<Item = Position> + 'a {
+        grid.neighbours8(position).map(|(position, _)| position)
+    }
+}
+
+/// The first cell which isn't static in each of the 8 directions.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Visible;
+
+impl<C: Cell> Neighbourhood<C> for Visible {
+    fn neighbours<'a>(
+        &'a self,
+        grid: &'a Grid<C>,
+        position: Position,
+    ) -> impl Iterator<Item = Position> + 'a {
+        DIRECTIONS8.iter().filter_map(move |direction| {
+            grid.ray(position, *direction)
+                // rays along wrapping axes never end
+                .take(grid.cells().len())
+                .find(|(_, cell)| !cell.is_static())
+                .map(|(position, _)| position)
+        })
+    }
+}
+
+/// Computes the next state of a cell.
+pub trait Rule<C> {
+    fn next(&self, cell: C, alive_neighbours: usize) -> C;
+}
+
+/// Rule of the Life-like family: dead cells become alive with one of the `birth` counts of alive
+/// neighbours, alive cells survive with one of the `survival` counts.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Life {
+    /// bit `n` is set if `n` alive neighbours give birth to a cell
+    birth: u16,
+    /// bit `n` is set if a cell survives with `n` alive neighbours
+    survival: u16,
+}
+
+impl Life {
+    /// the highest count of alive neighbours a rule can refer to.
+    pub const MAX_NEIGHBOURS: usize = 8;
+
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let bits = |counts: &[usize]| {
+            counts
+                .iter()
+                .filter(|count| **count <= Self::MAX_NEIGHBOURS)
+                .fold(0, |bits, count| bits | 1 << count)
+        };
+
+        Self {
+            birth: bits(birth),
+            survival: bits(survival),
+        }
+    }
+
+    /// the rule of day 11: people take empty seats without occupied neighbours and leave their
+    /// seat when at least `tolerance` neighbours are occupied.
+    pub fn seats(tolerance: usize) -> Self {
+        let survival: Vec<usize> = (0..tolerance).collect();
+
+        Self::new(&[0], &survival)
+    }
+}
+
+impl<C: Cell> Rule<C> for Life {
+    fn next(&self, cell: C, alive_neighbours: usize) -> C {
+        let counts = match cell.is_alive() {
+            true => self.survival,
+            false => self.birth,
+        };
+
+        cell.with_alive(
+            alive_neighbours <= Self::MAX_NEIGHBOURS && counts & 1 << alive_neighbours != 0,
+        )
+    }
+}
+
+/// writes the rule as `B<counts>/S<counts>`.
+impl Display for Life {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |bits: u16| -> String {
+            (0..=Self::MAX_NEIGHBOURS)
+                .filter(|count| bits & 1 << count != 0)
+                .map(|count| count.to_string())
+                .collect()
+        };
+
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))
+    }
+}
+
+/// parses rules written as `B<counts>/S<counts>`, e.g. `B3/S23`.
+impl FromStr for Life {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid rule `{}`, expected `B<counts>/S<counts>`", s);
+        let counts = |counts: &str| -> Result<Vec<usize>, String> {
+            counts
+                .chars()
+                .map(|c| match c.to_digit(10) {
+                    Some(count) if count as usize <= Self::MAX_NEIGHBOURS => Ok(count as usize),
+                    _ => Err(invalid()),
+                })
+                .collect()
+        };
+
+        let (birth, survival) = s.trim().split_once('/').ok_or_else(invalid)?;
+        let birth = birth
+            .strip_prefix(|c| c == 'B' || c == 'b')
+            .ok_or_else(invalid)?;
+        let survival = survival
+            .strip_prefix(|c| c == 'S' || c == 's')
+            .ok_or_else(invalid)?;
+
+        Ok(Life::new(&counts(birth)?, &counts(survival)?))
+    }
+}
+
+/// Hasher mixing every written value with a step of the splitmix64 generator. Keys of single cells
+/// are hashed for every change, the default hasher is too slow for that.
+#[derive(Debug, Default)]
+struct Mixer(u64);
+
+impl Hasher for Mixer {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.write_u64(u64::from(*byte));
+        }
+    }
+
+    fn write_u8(&mut self, n: u8) {
+        self.write_u64(u64::from(n));
+    }
+
+    fn write_u16(&mut self, n: u16) {
+        self.write_u64(u64::from(n));
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write_u64(u64::from(n));
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        let mut z = (self.0 ^ n).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        self.0 = z ^ (z >> 31);
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+}
+
+/// How [`Automaton::run`] ended.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    /// the generation doesn't change anymore
+    Stable(usize),
+    /// the generations starting with `start` repeat every `period` generations
+    Cycle { start: usize, period: usize },
+    /// the limit of generations was reached first
+    Limit,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Stable(generation) => write!(f, "stable from generation {}", generation),
+            Outcome::Cycle { start, period } => write!(
+                f,
+                "repeats every {} generations from generation {}",
+                period, start
+            ),
+            Outcome::Limit => f.write_str("reached the limit of generations"),
+        }
+    }
+}
+
+/// Cells of a grid evolving by a rule.
+#[derive(Debug, Clone)]
+pub struct Automaton<C, R> {
+    grid: Grid<C>,
+    rule: R,
+    /// the cells of the next generation, swapped with those of the grid
+    next: Vec<C>,
+    /// index of every cell which isn't static
+    cells: Vec<u32>,
+    /// the neighbours of cell `i` are `neighbours[starts[i]..starts[i + 1]]`
+    starts: Vec<u32>,
+    neighbours: Vec<u32>,
+    generation: usize,
+    /// hash of the cells, the xor of the [`key`](Automaton::key)s of the cells which aren't static
+    fingerprint: u64,
+}
+
+impl<C: Cell, R: Rule<C>> Automaton<C, R> {
+    pub fn new(grid: Grid<C>, neighbourhood: &impl Neighbourhood<C>, rule: R) -> Self {
+        let index = |position| grid.cell_index(position).expect("neighbours are inside") as u32;
+
+        let mut cells = Vec::new();
+        let mut starts = vec![0];
+        let mut neighbours = Vec::new();
+        for (position, _) in grid.iter().filter(|(_, cell)| !cell.is_static()) {
+            neighbours.extend(
+                neighbourhood
+                    .neighbours(&grid, position)
+                    .filter(|neighbour| !grid[*neighbour].is_static())
+                    .map(index),
+            );
+
+            cells.push(index(position));
+            starts.push(neighbours.len() as u32);
+        }
+
+        let fingerprint = cells
+            .iter()
+            .map(|index| Self::key(*index as usize, grid.cells()[*index as usize]))
+            .fold(0, |fingerprint, key| fingerprint ^ key);
+
+        Self {
+            next: grid.cells().to_vec(),
+            grid,
+            rule,
+            cells,
+            starts,
+            neighbours,
+            generation: 0,
+            fingerprint,
+        }
+    }
+
+    /// hash of `cell` at `index`. Changing a cell only changes its key, so the fingerprint of a
+    /// generation is updated with the cells which changed instead of hashing all of them.
+    fn key(index: usize, cell: C) -> u64 {
+        let mut hasher = Mixer::default();
+        (index, cell).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn grid(&self) -> &Grid<C> {
+        &self.grid
+    }
+
+    /// number of generations computed so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// computes the next generation, returns whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let current = self.grid.cells();
+        let mut changed = false;
+
+        for (cell, index) in self.cells.iter().enumerate() {
+            let neighbours =
+                &self.neighbours[self.starts[cell] as usize..self.starts[cell + 1] as usize];
+            let alive = neighbours
+                .iter()
+                .filter(|neighbour| current[**neighbour as usize].is_alive())
+                .count();
+
+            let (state, index) = (current[*index as usize], *index as usize);
+            let next = self.rule.next(state, alive);
+
+            if next != state {
+                changed = true;
+                self.fingerprint ^= Self::key(index, state) ^ Self::key(index, next);
+            }
+            self.next[index] = next;
+        }
+
+        self.grid.swap_cells(&mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    pub fn count_alive(&self) -> usize {
+        self.grid
+            .cells()
+            .iter()
+            .filter(|cell| cell.is_alive())
+            .count()
+    }
+}
+
+/// running needs a copy of the rule, to compute earlier generations again.
+impl<C: Cell, R: Rule<C> + Clone> Automaton<C, R> {
+    /// computes generations until the automaton is stable or repeats an earlier generation, but not
+    /// beyond generation `max_generations`.
+    ///
+    /// Generations are recognized by a hash of their cells, so only the hashes are kept. A repeated
+    /// hash is confirmed by computing the earlier generation again, hashes of different generations
+    /// may collide.
+    pub fn run(&mut self, max_generations: usize) -> Outcome {
+        self.run_with(max_generations, |_| ())
+    }
+
+    /// like [`run`](Automaton::run), passing the grid of every generation to `observe`, starting
+    /// with the current one. Generations equal to an earlier one are left out, so every grid passed
+    /// is different.
+    pub fn run_with(
+        &mut self,
+        max_generations: usize,
+        mut observe: impl FnMut(&Grid<C>),
+    ) -> Outcome {
+        observe(&self.grid);
+
+        let initial = self.clone();
+        let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+        seen.insert(self.fingerprint, vec![self.generation]);
+
+        while self.generation < max_generations {
+            cancel::checkpoint();
+
+            if !self.step() {
+                return Outcome::Stable(self.generation - 1);
+            }
+
+            let earlier = seen.entry(self.fingerprint).or_default();
+            if let Some(start) = earlier
+                .iter()
+                .copied()
+                .find(|start| initial.replay(*start) == self.grid)
+            {
+                return Outcome::Cycle {
+                    start,
+                    period: self.generation - start,
+                };
+            }
+            earlier.push(self.generation);
+            observe(&self.grid);
+        }
+
+        Outcome::Limit
+    }
+
+    /// the grid of `generation`, which must not be before the current one.
+    fn replay(&self, generation: usize) -> Grid<C> {
+        let mut automaton = self.clone();
+        while automaton.generation < generation {
+            automaton.step();
+        }
+
+        automaton.grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::automaton::*;
+    use crate::grid::Edge;
+    use crate::parse::Invalid;
+
+    fn life(input: &str) -> Grid<bool> {
+        Grid::parse(0, input, |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(Invalid::new(0, "expected `#` or `.`")),
+        })
+        .unwrap()
+    }
+
+    fn render(grid: &Grid<bool>) -> String {
+        grid.map(|_, alive| if *alive { '#' } else { '.' })
+            .to_string()
+    }
+
+    #[test]
+    fn test_rule_strings() {
+        let rule: Life = "B3/S23".parse().unwrap();
+
+        assert_eq!(Life::new(&[3], &[2, 3]), rule);
+        assert_eq!("B3/S23", rule.to_string());
+        assert_eq!("B0/S0123", Life::seats(4).to_string());
+        assert_eq!(Ok(Life::seats(5)), "b0/s01234".parse());
+        assert_eq!(Ok(Life::new(&[], &[])), "B/S".parse());
+        assert!("B9/S23".parse::<Life>().is_err());
+        assert!("S23/B3".parse::<Life>().is_err());
+    }
+
+    #[test]
+    fn test_blinker() {
+        let mut automaton = Automaton::new(
+            life(".....\n..#..\n..#..\n..#..\n....."),
+            &Adjacent,
+            "B3/S23".parse::<Life>().unwrap(),
+        );
+
+        assert!(automaton.step());
+        assert_eq!(
+            ".....\n.....\n.###.\n.....\n.....\n",
+            render(automaton.grid())
+        );
+        assert!(automaton.step());
+        assert_eq!(
+            ".....\n..#..\n..#..\n..#..\n.....\n",
+            render(automaton.grid())
+        );
+        assert_eq!((2, 3), (automaton.generation(), automaton.count_alive()));
+    }
+
+    #[test]
+    fn test_outcomes() {
+        let rule = Life::new(&[3], &[2, 3]);
+        let run = |input, max_generations| {
+            Automaton::new(life(input), &Adjacent, rule).run(max_generations)
+        };
+        let blinker = ".....\n..#..\n..#..\n..#..\n.....";
+
+        assert_eq!(Outcome::Stable(0), run("....\n.##.\n.##.\n....", 10));
+        // a single cell dies in the first generation
+        assert_eq!(Outcome::Stable(1), run("...\n.#.\n...", 10));
+        assert_eq!(
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            },
+            run(blinker, 10)
+        );
+        assert_eq!(Outcome::Limit, run(blinker, 1));
+    }
+
+    #[test]
+    fn test_run_with_observes_every_generation() {
+        let observe = |input| {
+            let mut grids = Vec::new();
+            let outcome = Automaton::new(life(input), &Adjacent, Life::new(&[3], &[2, 3]))
+                .run_with(10, |grid| grids.push(render(grid)));
+            (outcome, grids)
+        };
+
+        let (outcome, grids) = observe("...\n.#.\n...");
+        assert_eq!(Outcome::Stable(1), outcome);
+        assert_eq!(vec!["...\n.#.\n...\n", "...\n...\n...\n"], grids);
+
+        let (_, grids) = observe(".....\n..#..\n..#..\n..#..\n.....");
+        assert_eq!(2, grids.len());
+    }
+
+    /// cells of which every state has the same hash, so the fingerprint never changes.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    struct Colliding(bool);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, _: &mut H) {}
+    }
+
+    impl Cell for Colliding {
+        fn is_alive(self) -> bool {
+            self.0
+        }
+
+        fn with_alive(self, alive: bool) -> Self {
+            Colliding(alive)
+        }
+    }
+
+    #[test]
+    fn test_colliding_hashes_are_no_cycle() {
+        let rule = Life::new(&[3], &[2, 3]);
+        let run = |input| {
+            let grid = life(input)
+                .with_edges(Edge::Wrapping, Edge::Wrapping)
+                .map(|_, alive| Colliding(*alive));
+            Automaton::new(grid, &Adjacent, rule).run(100)
+        };
+
+        assert_eq!(
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            },
+            run(".....\n..#..\n..#..\n..#..\n.....")
+        );
+        assert_eq!(
+            Outcome::Cycle {
+                start: 0,
+                period: 20
+            },
+            run(".#...\n..#..\n###..\n.....\n.....")
+        );
+    }
+
+    #[test]
+    fn test_glider_wraps_around() {
+        let glider = life(".#...\n..#..\n###..\n.....\n.....");
+        let mut automaton = Automaton::new(
+            glider.clone().with_edges(Edge::Wrapping, Edge::Wrapping),
+            &Adjacent,
+            Life::new(&[3], &[2, 3]),
+        );
+
+        // a glider moves by one cell diagonally every 4 generations
+        for _ in 0..20 {
+            automaton.step();
+        }
+        assert_eq!(render(&glider), render(automaton.grid()));
+    }
+}
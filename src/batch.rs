@@ -0,0 +1,119 @@
+//! Running the solutions of a day on several inputs, e.g. the inputs of several accounts.
+
+use crate::solution::{self, Outcome, Runner};
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Outcomes of every part of a day on every input of the day.
+#[derive(Debug)]
+pub struct Matrix {
+    pub day: u32,
+    pub parts: Vec<u32>,
+    /// path of the input and the outcome of every part, in the order of `parts`
+    pub rows: Vec<(PathBuf, Vec<Outcome>)>,
+}
+
+impl Matrix {
+    pub fn failures(&self) -> usize {
+        self.rows
+            .iter()
+            .flat_map(|(_, outcomes)| outcomes)
+            .filter(|outcome| !outcome.is_solved())
+            .count()
+    }
+}
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |path: &Path| {
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned()
+        };
+        let width = self
+            .rows
+            .iter()
+            .map(|(path, _)| name(path).len())
+            .chain(Some(6))
+            .max()
+            .unwrap();
+
+        write!(f, "{:<width$}", format!("day {}", self.day), width = width)?;
+        for part in &self.parts {
+            write!(f, "   {:<20} {:>10}", format!("part {}", part), "time")?;
+        }
+        writeln!(f)?;
+
+        for (path, outcomes) in &self.rows {
+            write!(f, "{:<width$}", name(path), width = width)?;
+
+            for outcome in outcomes {
+                match outcome {
+                    Outcome::Solved(run) => write!(
+                        f,
+                        "   {:<20} {:>10}",
+                        run.answer.to_line(),
+                        format!("{:.2?}", run.parse_time + run.solve_time)
+                    )?,
+                    Outcome::Failed(_) => write!(f, "   {:<20} {:>10}", "error", "-")?,
+                    Outcome::Panicked(_) => write!(f, "   {:<20} {:>10}", "panicked", "-")?,
+                    Outcome::TimedOut(limit) => {
+                        write!(f, "   {:<20} {:>10}", "timed out", format!("> {:?}", limit))?
+                    }
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// input files of `day` in `dir`, i.e. the `.txt` files in `dir/day<day>`, sorted by name.
+pub fn input_paths(dir: &Path, day: u32) -> io::Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir.join(format!("day{}", day)))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+
+    paths.retain(|path| path.is_file() && path.extension().is_some_and(|e| e == "txt"));
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// runs every runner, which must all belong to `day`, on every input in `paths`, cancelling runs
+/// that exceed `limit`.
+pub fn run(
+    day: u32,
+    runners: &[&dyn Runner],
+    paths: &[PathBuf],
+    limit: Option<Duration>,
+) -> Matrix {
+    let rows = paths
+        .iter()
+        .map(|path| {
+            let outcomes = match fs::read_to_string(path) {
+                Ok(input) => runners
+                    .iter()
+                    .map(|runner| solution::run_isolated(*runner, &input, limit))
+                    .collect(),
+                Err(e) => runners
+                    .iter()
+                    .map(|_| Outcome::Failed(format!("failed to read {}: {}", path.display(), e)))
+                    .collect(),
+            };
+
+            (path.clone(), outcomes)
+        })
+        .collect();
+
+    Matrix {
+        day,
+        parts: runners.iter().map(|runner| runner.part()).collect(),
+        rows,
+    }
+}
@@ -0,0 +1,88 @@
+//! Cooperative cancellation of long running solvers.
+//!
+//! The solvers don't take a token as argument, because their signatures are dictated by
+//! cargo-aoc. Instead the runner installs a [`Token`] for the current thread and long running
+//! loops call [`checkpoint`], which unwinds out of the solver once the token is cancelled.
+
+use std::cell::RefCell;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag telling solvers to stop.
+#[derive(Debug, Clone, Default)]
+pub struct Token(Arc<AtomicBool>);
+
+impl Token {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Panic payload of a solver that stopped at a [`checkpoint`] because it was cancelled.
+#[derive(Debug)]
+pub struct Cancelled;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+/// restores the previous token of the thread, even if the solver unwinds.
+struct Restore(Option<Token>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+    }
+}
+
+/// runs `f` with `token` as the token checked by [`checkpoint`] on the current thread.
+pub fn with_token<R>(token: &Token, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT.with(|current| current.borrow_mut().replace(token.clone()));
+    let _restore = Restore(previous);
+
+    f()
+}
+
+/// unwinds with [`Cancelled`] if the token of the current thread has been cancelled. Does nothing
+/// if there is no token, e.g. when running through cargo-aoc.
+pub fn checkpoint() {
+    let cancelled = CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    });
+
+    if cancelled {
+        // unlike `panic!` this doesn't invoke the panic hook, a cancellation isn't a bug
+        panic::resume_unwind(Box::new(Cancelled));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancel::*;
+
+    #[test]
+    fn test_checkpoint_unwinds_once_cancelled() {
+        let token = Token::new();
+        checkpoint();
+
+        with_token(&token, checkpoint);
+        token.cancel();
+        let result = panic::catch_unwind(|| with_token(&token, checkpoint));
+
+        assert!(result.unwrap_err().is::<Cancelled>());
+        // the token is only installed while running `f`
+        checkpoint();
+    }
+}
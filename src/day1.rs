@@ -1,23 +1,61 @@
+use crate::answer::Answer;
+use crate::cancel;
+use crate::params::{self, value};
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools as _;
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Params {
+    /// sum of the entries to find
+    pub sum: u32,
+    /// number of entries summing up to `sum` in part 2
+    pub summands: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            sum: 2020,
+            summands: 3,
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+        match key {
+            "sum" => self.sum = value(key, s)?,
+            "summands" => self.summands = value(key, s)?,
+            _ => return Err(params::unknown(key, &self.values())),
+        }
+
+        Ok(())
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("sum", self.sum.to_string()),
+            ("summands", self.summands.to_string()),
+        ]
+    }
+}
+
 #[aoc_generator(day1)]
-fn input_generator(input: &str) -> Vec<u32> {
-    input
-        .lines()
-        .map(|line| line.parse::<u32>().unwrap())
-        .collect()
+pub(crate) fn input_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::lines(1, input, |line| parse::number(line, line))
 }
 
 /// find a pair of numbers that sums up to `sum`
 fn find_pair(mut numbers: Vec<u32>, sum: u32) -> Option<(u32, u32)> {
-    let (mut l, mut r) = (0, numbers.len() - 1);
     numbers.sort_unstable();
+    let (mut l, mut r) = (0, numbers.len().checked_sub(1)?);
 
     while l < r {
         match (numbers[l], numbers[r]) {
-            (a, b) if a + b == sum => return Some((a, b)),
-            (a, b) if a + b < sum => l += 1,
+            (a, b) if u64::from(a) + u64::from(b) == u64::from(sum) => return Some((a, b)),
+            (a, b) if u64::from(a) + u64::from(b) < u64::from(sum) => l += 1,
             _ => r -= 1,
         }
     }
@@ -27,27 +65,136 @@ fn find_pair(mut numbers: Vec<u32>, sum: u32) -> Option<(u32, u32)> {
 
 /// Find a set of `n_summands` that sums up to `sum` by bruteforcing them.
 fn find_summands(numbers: &[u32], n_summands: usize, sum: u32) -> Option<Vec<u32>> {
-    let min = numbers.iter().copied().min().unwrap_or(u32::min_value());
+    // every summand leaves room for the others, which are at least as large as the smallest number
+    let min = numbers.iter().copied().min().unwrap_or(u32::MIN);
+    let others = u64::from(min) * n_summands.saturating_sub(1) as u64;
 
     numbers
         .iter()
         .copied()
-        .filter(|x| *x < sum - min)
+        .filter(|x| u64::from(*x) + others <= u64::from(sum))
         .combinations(n_summands)
-        .find(|x| x.iter().sum::<u32>() == sum)
+        .inspect(|_| cancel::checkpoint())
+        .find(|x| x.iter().copied().map(u64::from).sum::<u64>() == u64::from(sum))
 }
 
-#[aoc(day1, part1)]
-pub fn solve_part_1(numbers: &[u32]) -> u32 {
-    let (a, b) = find_pair(numbers.to_vec(), 2020).expect("no solution found");
+pub(crate) fn solve_part_1(numbers: &[u32], params: &Params) -> u32 {
+    let (a, b) = find_pair(numbers.to_vec(), params.sum).expect("no solution found");
 
     a * b
 }
 
-#[aoc(day1, part2)]
-pub fn solve_part_2(numbers: &[u32]) -> u32 {
-    find_summands(numbers, 3, 2020)
+pub(crate) fn solve_part_2(numbers: &[u32], params: &Params) -> u32 {
+    find_summands(numbers, params.summands, params.sum)
         .expect("no solution found")
         .iter()
         .product()
 }
+
+#[aoc(day1, part1)]
+pub(crate) fn part_1(numbers: &[u32]) -> u32 {
+    solve_part_1(numbers, &Params::default())
+}
+
+#[aoc(day1, part2)]
+pub(crate) fn part_2(numbers: &[u32]) -> u32 {
+    solve_part_2(numbers, &Params::default())
+}
+
+#[derive(Default)]
+pub(crate) struct Day1 {
+    pub params: Params,
+}
+
+impl Solution for Day1 {
+    type Input<'a> = Vec<u32>;
+
+    fn day(&self) -> u32 {
+        1
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input, &self.params).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input, &self.params).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day1::*;
+    use proptest::prelude::*;
+
+    /// first pair of numbers, in input order, that sums up to `sum`
+    fn find_pair_naive(numbers: &[u32], sum: u32) -> Option<(u32, u32)> {
+        numbers
+            .iter()
+            .copied()
+            .tuple_combinations()
+            .find(|(a, b)| a + b == sum)
+    }
+
+    fn find_summands_naive(numbers: &[u32], n_summands: usize, sum: u32) -> Option<Vec<u32>> {
+        numbers
+            .iter()
+            .copied()
+            .combinations(n_summands)
+            .find(|x| x.iter().sum::<u32>() == sum)
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(None, find_pair(vec![], 0));
+        assert_eq!(None, find_pair(vec![2020], 2020));
+        assert_eq!(Some((0, 2020)), find_pair(vec![2020, 0], 2020));
+        assert_eq!(Some((1010, 1010)), find_pair(vec![1010, 1010], 2020));
+        assert_eq!(None, find_summands(&[], 3, 2020));
+        assert_eq!(
+            Some(vec![2020, 0, 0]),
+            find_summands(&[2020, 0, 0], 3, 2020)
+        );
+        assert_eq!(None, find_summands(&[3000, 2020], 1, 10));
+    }
+
+    proptest! {
+        #[test]
+        fn test_find_pair_matches_naive(
+            numbers in prop::collection::vec(0..50u32, 0..20),
+            sum in 0..100u32,
+        ) {
+            let pair = find_pair(numbers.clone(), sum);
+            prop_assert_eq!(find_pair_naive(&numbers, sum).is_some(), pair.is_some());
+
+            if let Some((a, b)) = pair {
+                let mut rest = numbers.clone();
+                rest.remove(rest.iter().position(|x| *x == a).unwrap());
+
+                prop_assert_eq!(sum, a + b);
+                prop_assert!(rest.contains(&b));
+            }
+        }
+
+        #[test]
+        fn test_find_summands_matches_naive(
+            numbers in prop::collection::vec(0..50u32, 0..12),
+            n_summands in 0..4usize,
+            sum in 0..100u32,
+        ) {
+            prop_assert_eq!(
+                find_summands_naive(&numbers, n_summands, sum),
+                find_summands(&numbers, n_summands, sum)
+            );
+        }
+    }
+
+    examples! {
+        day 1;
+        example: part_1, part_2;
+    }
+}
@@ -1,19 +1,74 @@
+use crate::answer::Answer;
+use crate::params::{self, value};
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashMap;
 
-type Adapter = u64;
+pub(crate) type Adapter = u64;
 
-#[aoc_generator(day10)]
-fn input_generator(input: &str) -> Vec<Adapter> {
-    let mut adapters: Vec<u64> = input.lines().map(|line| line.parse().unwrap()).collect();
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Params {
+    /// smallest difference of joltage an adapter accepts
+    pub min_difference: u64,
+    /// largest difference of joltage an adapter accepts, also the one of the built in adapter
+    pub max_difference: u64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            min_difference: 1,
+            max_difference: 3,
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+        match key {
+            "min_difference" => self.min_difference = value(key, s)?,
+            "max_difference" => self.max_difference = value(key, s)?,
+            _ => return Err(params::unknown(key, &self.values())),
+        }
+
+        Ok(())
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("min_difference", self.min_difference.to_string()),
+            ("max_difference", self.max_difference.to_string()),
+        ]
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.min_difference > self.max_difference {
+            return Err(format!(
+                "min_difference {} is larger than max_difference {}",
+                self.min_difference, self.max_difference
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// parses the adapters and adds the outlet and the built in adapter of the device.
+pub(crate) fn parse_adapters(input: &str, params: &Params) -> Result<Vec<Adapter>, ParseError> {
+    let mut adapters: Vec<u64> = parse::lines(10, input, |line| parse::number(line, line))?;
 
     // add power source
     adapters.push(0);
     adapters.sort_unstable();
     // add built in adapter
-    adapters.push(adapters.last().unwrap() + 3);
+    adapters.push(adapters.last().unwrap() + params.max_difference);
 
-    adapters
+    Ok(adapters)
+}
+
+#[aoc_generator(day10)]
+pub(crate) fn input_generator(input: &str) -> Result<Vec<Adapter>, ParseError> {
+    parse_adapters(input, &Params::default())
 }
 
 fn get_deltas(adapters: &[Adapter]) -> Vec<u64> {
@@ -23,15 +78,15 @@ fn get_deltas(adapters: &[Adapter]) -> Vec<u64> {
         .collect()
 }
 
-#[aoc(day10, part1)]
-fn solve_part_1(adapters: &[Adapter]) -> usize {
+pub(crate) fn solve_part_1(adapters: &[Adapter], params: &Params) -> usize {
     let deltas = get_deltas(adapters)
         .iter()
         .fold((0, 0), |mut deltas, delta| {
-            match delta {
-                1 => deltas.0 += 1,
-                3 => deltas.1 += 1,
-                _ => {}
+            if *delta == params.min_difference {
+                deltas.0 += 1;
+            }
+            if *delta == params.max_difference {
+                deltas.1 += 1;
             }
 
             deltas
@@ -40,18 +95,148 @@ fn solve_part_1(adapters: &[Adapter]) -> usize {
     deltas.0 * deltas.1
 }
 
+pub(crate) fn solve_part_2(adapters: &[Adapter], params: &Params) -> u64 {
+    // arrangements[i] is the number of ways to get from adapter i to the device
+    let mut arrangements = vec![0; adapters.len()];
+
+    for (index, adapter) in adapters.iter().enumerate().rev() {
+        arrangements[index] = match index + 1 == adapters.len() {
+            true => 1,
+            false => adapters[index + 1..]
+                .iter()
+                .zip(&arrangements[index + 1..])
+                .take_while(|(next, _)| *next - adapter <= params.max_difference)
+                .filter(|(next, _)| *next - adapter >= params.min_difference)
+                .map(|(_, arrangements)| arrangements)
+                .sum(),
+        };
+    }
+
+    arrangements[0]
+}
+
+#[aoc(day10, part1)]
+pub(crate) fn part_1(adapters: &[Adapter]) -> usize {
+    solve_part_1(adapters, &Params::default())
+}
+
 #[aoc(day10, part2)]
-fn solve_part_2(adapters: &[Adapter]) -> u64 {
-    let mut adapter_map: HashMap<u64, u64> = HashMap::with_capacity(adapters.len());
-    adapter_map.insert(*adapters.last().unwrap() + 3, 1);
+pub(crate) fn part_2(adapters: &[Adapter]) -> u64 {
+    solve_part_2(adapters, &Params::default())
+}
+
+#[derive(Default)]
+pub(crate) struct Day10 {
+    pub params: Params,
+}
+
+impl Solution for Day10 {
+    type Input<'a> = Vec<Adapter>;
+
+    fn day(&self) -> u32 {
+        10
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        parse_adapters(input, &self.params)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input, &self.params).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input, &self.params).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day10::*;
+    use proptest::prelude::*;
+
+    /// counts the arrangements by trying every subset of the adapters between the outlet and the
+    /// device
+    fn solve_part_2_naive(adapters: &[Adapter]) -> u64 {
+        let between = adapters.len().saturating_sub(2);
 
-    for adapter in adapters.iter().copied().rev() {
-        let with_delta_1 = adapter_map.get(&(adapter + 1)).copied().unwrap_or(0);
-        let with_delta_2 = adapter_map.get(&(adapter + 2)).copied().unwrap_or(0);
-        let with_delta_3 = adapter_map.get(&(adapter + 3)).copied().unwrap_or(0);
+        (0..1u64 << between)
+            .filter(|subset| {
+                let chain: Vec<Adapter> = adapters
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| {
+                        *index == 0
+                            || *index == adapters.len() - 1
+                            || subset & 1 << (index - 1) != 0
+                    })
+                    .map(|(_, adapter)| *adapter)
+                    .collect();
 
-        adapter_map.insert(adapter, with_delta_1 + with_delta_2 + with_delta_3);
+                chain
+                    .windows(2)
+                    .all(|pair| (1..=3).contains(&(pair[1] - pair[0])))
+            })
+            .count() as u64
     }
 
-    adapter_map.get(&0).copied().unwrap()
+    fn adapters(input: &[u64]) -> Vec<Adapter> {
+        let input: Vec<String> = input.iter().map(u64::to_string).collect();
+        input_generator(&input.join("\n")).unwrap()
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(1, part_2(&adapters(&[1])));
+        assert_eq!(1, part_2(&adapters(&[0])));
+        assert_eq!(2, part_2(&adapters(&[2, 2])));
+        assert_eq!(0, part_2(&adapters(&[4])));
+    }
+
+    #[test]
+    fn test_differences() {
+        let params = Params {
+            min_difference: 2,
+            max_difference: 4,
+        };
+        let adapters = parse_adapters("2\n3\n6\n8", &params).unwrap();
+
+        assert_eq!(vec![0, 2, 3, 6, 8, 12], adapters);
+        // the differences are 2 1 3 2 4
+        assert_eq!(2, solve_part_1(&adapters, &params));
+        // 0 2 6 8 12 and 0 3 6 8 12
+        assert_eq!(2, solve_part_2(&adapters, &params));
+    }
+
+    #[test]
+    fn test_differences_are_validated_together() {
+        let overrides = |spec: &str| spec.parse::<params::Overrides>().unwrap().apply(10);
+
+        assert_eq!(
+            Ok(Params {
+                min_difference: 0,
+                max_difference: 0,
+            }),
+            overrides("10.min_difference=0\n10.max_difference=0")
+        );
+        assert_eq!(
+            Err("day 10: min_difference 4 is larger than max_difference 3".to_string()),
+            overrides("10.min_difference=4")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_solve_part_2_matches_naive(input in prop::collection::vec(0..20u64, 1..12)) {
+            let adapters = adapters(&input);
+
+            prop_assert_eq!(solve_part_2_naive(&adapters), part_2(&adapters));
+        }
+    }
+
+    examples! {
+        day 10;
+        example1: part_1, part_2;
+        example2: part_1, part_2;
+    }
 }
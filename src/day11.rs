@@ -1,179 +1,74 @@
+use crate::answer::Answer;
+use crate::automaton::{Adjacent, Automaton, Cell, Life, Neighbourhood, Outcome, Visible};
+use crate::grid::Grid;
+use crate::params::{self, value};
+use crate::parse::{Invalid, ParseError};
+use crate::render::Palette;
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fmt::{self, Display};
 
-type Position = (i32, i32);
-type Vector = Position;
-
-#[derive(Debug)]
-struct Ferry {
-    seats: HashMap<Position, State>,
-}
-
-const fn neighbour_coordinates_and_vectors((row, col): Position) -> [(Position, Vector); 8] {
-    [
-        ((row - 1, col - 1), (-1, -1)),
-        ((row, col - 1), (0, -1)),
-        ((row + 1, col - 1), (1, -1)),
-        ((row - 1, col), (-1, 0)),
-        ((row + 1, col), (1, 0)),
-        ((row - 1, col + 1), (-1, 1)),
-        ((row, col + 1), (0, 1)),
-        ((row + 1, col + 1), (1, 1)),
-    ]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Vec<String>", try_from = "Vec<String>")
+)]
+pub(crate) struct Ferry {
+    seats: Grid<State>,
 }
 
 impl Ferry {
-    pub fn new(seats: HashMap<Position, State>) -> Self {
+    pub fn new(seats: Grid<State>) -> Self {
         Self { seats }
     }
+}
 
-    pub fn occupy_all(&mut self) {
-        self.seats
-            .values_mut()
-            .filter(|state| **state != State::Blocked)
-            .for_each(|state| *state = State::Occupied)
-    }
-
-    fn neighbours(&self, position: Position) -> Vec<(Position, State)> {
-        let neighbour_coordinates = neighbour_coordinates_and_vectors(position);
-
-        neighbour_coordinates
-            .iter()
-            .filter_map(|(coordinates, _)| {
-                self.seats
-                    .get(coordinates)
-                    .copied()
-                    .map(|state| (*coordinates, state))
-            })
-            .collect()
-    }
-
-    fn next_visible_seats(&self, position: Position) -> Vec<(Position, State)> {
-        let neighbour_coordinates = neighbour_coordinates_and_vectors(position);
-
-        neighbour_coordinates
-            .iter()
-            .filter_map(|((mut row, mut col), v)| {
-                while let Some(state) = self.seats.get(&(row, col)) {
-                    if *state != State::Blocked {
-                        return Some(((row, col), *state));
-                    }
-
-                    row += v.0;
-                    col += v.1;
-                }
-
-                None
-            })
-            .collect()
-    }
-
-    fn next_state1(&self, (position, state): (Position, State)) -> State {
-        let occupied_neighbours = self
-            .neighbours(position)
-            .iter()
-            .filter(|(_, state)| *state == State::Occupied)
-            .count();
-
-        match (state, occupied_neighbours) {
-            (State::Empty, 0) => State::Occupied,
-            (State::Occupied, n) if n >= 4 => State::Empty,
-            (state, _n) => state,
-        }
+impl Display for Ferry {
+    /// writes the seats in the format of the input.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.seats.fmt(f)
     }
+}
 
-    fn next_state2(&self, (position, state): (Position, State)) -> State {
-        let visible_occupied_seats = self
-            .next_visible_seats(position)
-            .iter()
-            .filter(|(_, state)| *state == State::Occupied)
-            .count();
-
-        match (state, visible_occupied_seats) {
-            (State::Empty, 0) => State::Occupied,
-            (State::Occupied, n) if n >= 5 => State::Empty,
-            (state, _n) => state,
-        }
+/// rows of the seat layout as in the input
+impl From<Ferry> for Vec<String> {
+    fn from(ferry: Ferry) -> Self {
+        ferry.to_string().lines().map(str::to_string).collect()
     }
+}
 
-    fn run_cycle1(&mut self) -> bool {
-        let mut changed = false;
-
-        let new_states: Vec<(Position, State)> = self
-            .seats
-            .iter()
-            .map(|(coordinates, state)| {
-                let new_state = self.next_state1((*coordinates, *state));
-
-                if *state != new_state {
-                    changed = true;
-                }
-
-                (*coordinates, new_state)
-            })
-            .collect();
-
-        for (position, state) in new_states {
-            self.seats.insert(position, state);
-        }
+impl TryFrom<Vec<String>> for Ferry {
+    type Error = String;
 
-        changed
+    fn try_from(rows: Vec<String>) -> Result<Self, Self::Error> {
+        input_generator(&rows.join("\n"))
+            .map(Ferry::new)
+            .map_err(|e| e.to_string())
     }
+}
 
-    fn run_cycle2(&mut self) -> bool {
-        let mut changed = false;
-
-        let new_states: Vec<(Position, State)> = self
+#[cfg(feature = "serde")]
+impl From<&Ferry> for crate::export::Table {
+    fn from(ferry: &Ferry) -> Self {
+        let rows = ferry
             .seats
             .iter()
-            .map(|(coordinates, state)| {
-                let new_state = self.next_state2((*coordinates, *state));
-
-                if *state != new_state {
-                    changed = true;
-                }
-
-                (*coordinates, new_state)
+            .map(|((column, row), state)| {
+                vec![row.to_string(), column.to_string(), state.to_string()]
             })
             .collect();
 
-        for (position, state) in new_states {
-            self.seats.insert(position, state);
+        Self {
+            header: vec!["row", "column", "state"],
+            rows,
         }
-
-        changed
-    }
-
-    fn count_occupied_seats(&self) -> usize {
-        self.seats
-            .values()
-            .copied()
-            .filter(|state| *state == State::Occupied)
-            .count()
-    }
-
-    #[allow(dead_code)]
-    fn print(&self, rows: i32, columns: i32) {
-        print!("\n");
-        for row in 0..rows {
-            for column in 0..columns {
-                let s = self
-                    .seats
-                    .get(&(row, column))
-                    .map(|state| state.to_string())
-                    .unwrap_or(" ".to_string());
-
-                print!("{}", s);
-            }
-            print!("\n");
-        }
-        print!("\n");
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum State {
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum State {
     Empty,
     Occupied,
     Blocked,
@@ -194,58 +89,302 @@ impl Display for State {
 }
 
 impl State {
-    pub fn from_char(c: char) -> Result<Self, ()> {
+    pub fn from_char(c: char) -> Result<Self, Invalid> {
         match c {
             '#' => Ok(Self::Occupied),
             'L' => Ok(Self::Empty),
             '.' => Ok(Self::Blocked),
-            _ => Err(()),
+            _ => Err(Invalid::new(
+                0,
+                format!("unexpected character `{}`, expected `L`, `#` or `.`", c),
+            )),
         }
     }
 }
 
-#[aoc_generator(day11)]
-fn input_generator(input: &str) -> HashMap<(i32, i32), State> {
-    input
-        .lines()
-        .enumerate()
-        .map(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(col, char)| ((row as i32, col as i32), State::from_char(char).unwrap()))
-                .collect::<Vec<((i32, i32), State)>>()
-        })
-        .flatten()
-        .fold(HashMap::new(), |mut map, (coordinates, state)| {
-            map.insert(coordinates, state);
-            map
-        })
+/// occupied seats are alive, the floor never changes.
+impl Cell for State {
+    fn is_alive(self) -> bool {
+        self == State::Occupied
+    }
+
+    fn with_alive(self, alive: bool) -> Self {
+        match (self, alive) {
+            (State::Blocked, _) => State::Blocked,
+            (_, true) => State::Occupied,
+            (_, false) => State::Empty,
+        }
+    }
+
+    fn is_static(self) -> bool {
+        self == State::Blocked
+    }
 }
 
-#[aoc(day11, part1)]
-fn solve_part_1(seats: &HashMap<(i32, i32), State>) -> usize {
-    let mut ferry = Ferry::new(seats.clone());
-    ferry.occupy_all();
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Params {
+    /// rule of part 1, where people look at the adjacent seats
+    pub adjacent_rule: Life,
+    /// rule of part 2, where people look at the first seat in every direction
+    pub visible_rule: Life,
+    /// generations after which the simulation gives up if the seats don't become stable
+    pub max_generations: usize,
+}
 
-    loop {
-        if !ferry.run_cycle1() {
-            break;
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            adjacent_rule: Life::seats(4),
+            visible_rule: Life::seats(5),
+            max_generations: 100_000,
         }
     }
+}
 
-    ferry.count_occupied_seats()
+impl params::Params for Params {
+    fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+        match key {
+            "adjacent_rule" => self.adjacent_rule = value(key, s)?,
+            "visible_rule" => self.visible_rule = value(key, s)?,
+            "max_generations" => self.max_generations = value(key, s)?,
+            _ => return Err(params::unknown(key, &self.values())),
+        }
+
+        Ok(())
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("adjacent_rule", self.adjacent_rule.to_string()),
+            ("visible_rule", self.visible_rule.to_string()),
+            ("max_generations", self.max_generations.to_string()),
+        ]
+    }
+}
+
+#[aoc_generator(day11)]
+pub(crate) fn input_generator(input: &str) -> Result<Grid<State>, ParseError> {
+    Grid::parse(11, input, State::from_char)
+}
+
+/// the seats as an automaton, starting with every seat occupied.
+fn occupy(
+    seats: &Grid<State>,
+    neighbourhood: &impl Neighbourhood<State>,
+    rule: Life,
+) -> Automaton<State, Life> {
+    let seats = seats.map(|_, state| state.with_alive(true));
+    Automaton::new(seats, neighbourhood, rule)
+}
+
+/// occupied seats once the layout is stable, starting with every seat occupied.
+fn simulate(
+    seats: &Grid<State>,
+    neighbourhood: &impl Neighbourhood<State>,
+    rule: Life,
+    params: &Params,
+) -> usize {
+    let mut automaton = occupy(seats, neighbourhood, rule);
+
+    match automaton.run(params.max_generations) {
+        Outcome::Stable(_) => automaton.count_alive(),
+        outcome => panic!("the seats never become stable, the layout {}", outcome),
+    }
+}
+
+pub(crate) fn solve_part_1(seats: &Grid<State>, params: &Params) -> usize {
+    simulate(seats, &Adjacent, params.adjacent_rule, params)
+}
+
+pub(crate) fn solve_part_2(seats: &Grid<State>, params: &Params) -> usize {
+    simulate(seats, &Visible, params.visible_rule, params)
+}
+
+/// colors of the floor, empty and occupied seats in rendered frames.
+pub(crate) fn palette() -> Palette {
+    "#202020,#3a6ea5,#f2b134".parse().unwrap()
+}
+
+/// passes every generation to `frame` until the seats are stable or repeat, colored with the
+/// indices of [`palette`].
+fn animate(
+    seats: &Grid<State>,
+    neighbourhood: &impl Neighbourhood<State>,
+    rule: Life,
+    params: &Params,
+    mut frame: impl FnMut(Grid<u8>),
+) {
+    occupy(seats, neighbourhood, rule).run_with(params.max_generations, |seats| {
+        frame(seats.map(|_, state| match state {
+            State::Blocked => 0,
+            State::Empty => 1,
+            State::Occupied => 2,
+        }))
+    });
+}
+
+pub(crate) fn animate_part_1(seats: &Grid<State>, params: &Params, frame: impl FnMut(Grid<u8>)) {
+    animate(seats, &Adjacent, params.adjacent_rule, params, frame)
+}
+
+pub(crate) fn animate_part_2(seats: &Grid<State>, params: &Params, frame: impl FnMut(Grid<u8>)) {
+    animate(seats, &Visible, params.visible_rule, params, frame)
+}
+
+#[aoc(day11, part1)]
+pub(crate) fn part_1(seats: &Grid<State>) -> usize {
+    solve_part_1(seats, &Params::default())
 }
 
 #[aoc(day11, part2)]
-fn solve_part_2(seats: &HashMap<(i32, i32), State>) -> usize {
-    let mut ferry = Ferry::new(seats.clone());
-    ferry.occupy_all();
+pub(crate) fn part_2(seats: &Grid<State>) -> usize {
+    solve_part_2(seats, &Params::default())
+}
 
-    loop {
-        if !ferry.run_cycle2() {
-            break;
+#[derive(Default)]
+pub(crate) struct Day11 {
+    pub params: Params,
+}
+
+impl Solution for Day11 {
+    type Input<'a> = Grid<State>;
+
+    fn day(&self) -> u32 {
+        11
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input, &self.params).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input, &self.params).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day11::*;
+    use proptest::prelude::*;
+
+    fn state() -> impl Strategy<Value = State> {
+        prop_oneof![
+            Just(State::Empty),
+            Just(State::Occupied),
+            Just(State::Blocked)
+        ]
+    }
+
+    #[test]
+    fn test_display_reproduces_input() {
+        let input = include_str!("../fixtures/2020/day11/example.txt");
+        let ferry = Ferry::new(input_generator(input).unwrap());
+
+        assert_eq!(input, ferry.to_string());
+    }
+
+    #[test]
+    fn test_second_generation() {
+        let seats = input_generator(include_str!("../fixtures/2020/day11/example.txt")).unwrap();
+        let occupied = seats.map(|_, state| state.with_alive(true));
+        let mut adjacent = Automaton::new(occupied.clone(), &Adjacent, Life::seats(4));
+        let mut visible = Automaton::new(occupied, &Visible, Life::seats(5));
+        adjacent.step();
+        visible.step();
+
+        assert_eq!(
+            "#.LL.L#.##\n#LLLLLL.L#\nL.L.L..L..\n#LLL.LL.L#\n#.LL.LL.LL\n\
+             #.LLLL#.##\n..L.L.....\n#LLLLLLLL#\n#.LLLLLL.L\n#.#LLLL.##\n",
+            adjacent.grid().to_string()
+        );
+        assert_eq!(
+            "#.LL.LL.L#\n#LLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\n\
+             L.LLLLL.LL\n..L.L.....\nLLLLLLLLL#\n#.LLLLLL.L\n#.LLLLL.L#\n",
+            visible.grid().to_string()
+        );
+    }
+
+    #[test]
+    fn test_outcome() {
+        let seats = input_generator(include_str!("../fixtures/2020/day11/example.txt")).unwrap();
+        let run = |rule: &str| {
+            let occupied = seats.map(|_, state| state.with_alive(true));
+            Automaton::new(occupied, &Adjacent, rule.parse::<Life>().unwrap()).run(100)
+        };
+
+        // the puzzle shows the layout becoming stable in the 5th round, the 1st occupies every seat
+        assert_eq!(Outcome::Stable(4), run("B0/S0123"));
+        // nobody stays, so the seats alternate between occupied and empty
+        assert_eq!(
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            },
+            run("B0/S")
+        );
+    }
+
+    #[test]
+    fn test_animation() {
+        let seats = input_generator(include_str!("../fixtures/2020/day11/example.txt")).unwrap();
+        let mut frames = Vec::new();
+        animate_part_1(&seats, &Params::default(), |frame| frames.push(frame));
+
+        // every seat occupied and the 4 rounds up to the stable layout
+        assert_eq!(5, frames.len());
+        assert_eq!(
+            (Some(&2), Some(&0)),
+            (frames[0].get((0, 0)), frames[0].get((1, 0)))
+        );
+        assert_eq!(
+            (Some(&2), Some(&1)),
+            (frames[1].get((0, 0)), frames[1].get((2, 0)))
+        );
+        assert_eq!(
+            37,
+            frames[4]
+                .cells()
+                .iter()
+                .filter(|color| **color == 2)
+                .count()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the seats never become stable")]
+    fn test_oscillating_seats() {
+        let params = Params {
+            adjacent_rule: "B0/S".parse().unwrap(),
+            ..Params::default()
+        };
+
+        solve_part_1(&input_generator("LL\nLL").unwrap(), &params);
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_round_trip(
+            rows in (1..12usize).prop_flat_map(|width| {
+                prop::collection::vec(prop::collection::vec(state(), width), 1..12)
+            })
+        ) {
+            let text: String = rows
+                .iter()
+                .map(|row| row.iter().map(ToString::to_string).collect::<String>() + "\n")
+                .collect();
+            let ferry = Ferry::new(input_generator(&text).unwrap());
+
+            prop_assert_eq!(&text, &ferry.to_string());
+            prop_assert_eq!(&ferry, &Ferry::new(input_generator(&ferry.to_string()).unwrap()));
         }
     }
 
-    ferry.count_occupied_seats()
+    examples! {
+        day 11;
+        example: part_1, part_2;
+    }
 }
@@ -1,35 +1,80 @@
+use crate::answer::Answer;
+use crate::parse::{self, Invalid, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::borrow::Cow;
+use std::fmt::{self, Display};
 
 #[derive(Debug, Eq, PartialEq)]
-struct Policy {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Policy {
     position_one: usize,
     position_two: usize,
     character: char,
 }
 
-type Password = String;
+impl Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} {}",
+            self.position_one, self.position_two, self.character
+        )
+    }
+}
 
-#[aoc_generator(day2)]
-fn input_generator(input: &str) -> Vec<(Policy, Password)> {
-    input
-        .lines()
-        .map(|line| {
-            let parts = line
-                .split(|c| c == ' ' || c == '-' || c == ':')
-                .collect::<Vec<&str>>();
+/// borrowed from the input text, owned when deserialized or generated for cargo-aoc.
+pub(crate) type Password<'a> = Cow<'a, str>;
 
-            let policy = Policy {
-                position_one: parts.get(0).unwrap().parse().unwrap(),
-                position_two: parts.get(1).unwrap().parse().unwrap(),
-                character: parts.get(2).unwrap().parse().unwrap(),
-            };
-            (policy, parts.last().unwrap().to_string())
-        })
-        .collect()
+#[cfg(feature = "serde")]
+impl crate::export::Record for (Policy, Password<'_>) {
+    const HEADER: &'static [&'static str] =
+        &["position_one", "position_two", "character", "password"];
+
+    fn record(&self) -> Vec<String> {
+        let (policy, password) = self;
+
+        vec![
+            policy.position_one.to_string(),
+            policy.position_two.to_string(),
+            policy.character.to_string(),
+            password.to_string(),
+        ]
+    }
 }
 
-#[aoc(day2, part1)]
-fn solve_part_1(input: &[(Policy, Password)]) -> usize {
+fn parse_line(line: &str) -> Result<(Policy, Password<'_>), Invalid> {
+    let (policy, password) = line
+        .split_once(": ")
+        .ok_or_else(|| Invalid::at(line, line, "expected `<policy>: <password>`"))?;
+    let (positions, character) = policy
+        .split_once(' ')
+        .ok_or_else(|| Invalid::at(line, policy, "expected `<min>-<max> <character>`"))?;
+    let (position_one, position_two) = positions
+        .split_once('-')
+        .ok_or_else(|| Invalid::at(line, positions, "expected `<min>-<max>`"))?;
+
+    let position = |fragment| match parse::number(line, fragment)? {
+        0 => Err(Invalid::at(line, fragment, "positions start at 1")),
+        position => Ok(position),
+    };
+
+    let policy = Policy {
+        position_one: position(position_one)?,
+        position_two: position(position_two)?,
+        character: character
+            .parse()
+            .map_err(|_| Invalid::at(line, character, "expected a single character"))?,
+    };
+
+    Ok((policy, password.into()))
+}
+
+pub(crate) fn input_generator(input: &str) -> Result<Vec<(Policy, Password<'_>)>, ParseError> {
+    parse::lines(2, input, parse_line)
+}
+
+pub(crate) fn solve_part_1(input: &[(Policy, Password)]) -> usize {
     input
         .iter()
         .filter(|(policy, password)| {
@@ -40,33 +85,78 @@ fn solve_part_1(input: &[(Policy, Password)]) -> usize {
         .count()
 }
 
-#[aoc(day2, part2)]
-fn solve_part_2(input: &[(Policy, Password)]) -> usize {
+pub(crate) fn solve_part_2(input: &[(Policy, Password)]) -> usize {
     input
         .iter()
         .filter(|(policy, password)| {
             let chars = password.chars().collect::<Vec<char>>();
-            let pos1 = chars.get(policy.position_one - 1).unwrap();
-            let pos2 = chars.get(policy.position_two - 1).unwrap();
+            // positions outside of the password don't match, the parser only rejects 0
+            let matches = |position: usize| {
+                position.checked_sub(1).and_then(|index| chars.get(index))
+                    == Some(&policy.character)
+            };
 
-            (*pos1 == policy.character) ^ (*pos2 == policy.character)
+            matches(policy.position_one) ^ matches(policy.position_two)
         })
         .count()
 }
 
+/// the policies with owned passwords, cargo-aoc keeps the generated input next to the input text
+/// so it can't borrow from it.
+#[aoc_generator(day2)]
+pub(crate) fn owned_generator(input: &str) -> Result<Vec<(Policy, Password<'static>)>, ParseError> {
+    Ok(input_generator(input)?
+        .into_iter()
+        .map(|(policy, password)| (policy, Cow::Owned(password.into_owned())))
+        .collect())
+}
+
+#[aoc(day2, part1)]
+pub(crate) fn part_1(input: &[(Policy, Password)]) -> usize {
+    solve_part_1(input)
+}
+
+#[aoc(day2, part2)]
+pub(crate) fn part_2(input: &[(Policy, Password)]) -> usize {
+    solve_part_2(input)
+}
+
+pub(crate) struct Day2;
+
+impl Solution for Day2 {
+    type Input<'a> = Vec<(Policy, Password<'a>)>;
+
+    fn day(&self) -> u32 {
+        2
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::day2::*;
+    use proptest::prelude::*;
 
     lazy_static! {
-        static ref INPUT: Vec<(Policy, Password)> = vec![
+        static ref INPUT: Vec<(Policy, Password<'static>)> = vec![
             (
                 Policy {
                     position_one: 1,
                     position_two: 3,
                     character: 'a'
                 },
-                "abcde".to_string()
+                "abcde".into()
             ),
             (
                 Policy {
@@ -74,7 +164,7 @@ mod tests {
                     position_two: 3,
                     character: 'b'
                 },
-                "cdefg".to_string()
+                "cdefg".into()
             ),
             (
                 Policy {
@@ -82,7 +172,7 @@ mod tests {
                     position_two: 9,
                     character: 'c'
                 },
-                "ccccccccc".to_string()
+                "ccccccccc".into()
             ),
         ];
     }
@@ -91,7 +181,7 @@ mod tests {
     fn test_input_generator() {
         let input = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc";
 
-        assert_eq!(input_generator(input), INPUT.as_slice());
+        assert_eq!(input_generator(input).unwrap(), INPUT.as_slice());
     }
 
     #[test]
@@ -103,4 +193,51 @@ mod tests {
     fn test_part_2() {
         assert_eq!(1, solve_part_2(INPUT.as_slice()));
     }
+
+    #[test]
+    fn test_positions_outside_of_the_password() {
+        let policy = |position_one, position_two| Policy {
+            position_one,
+            position_two,
+            character: 'a',
+        };
+
+        assert_eq!(1, solve_part_2(&[(policy(1, 5), "ab".into())]));
+        assert_eq!(0, solve_part_2(&[(policy(4, 5), "ab".into())]));
+        assert_eq!(1, solve_part_2(&[(policy(0, 1), "ab".into())]));
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_round_trip(
+            position_one in 1..30usize,
+            position_two in 1..30usize,
+            character in "[a-z]",
+            password in "[a-z]{1,20}",
+        ) {
+            let policy = Policy {
+                position_one,
+                position_two,
+                character: character.parse().unwrap(),
+            };
+            let line = format!("{}: {}", policy, password);
+
+            prop_assert_eq!((policy, password.as_str().into()), parse_line(&line).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_owned_generator() {
+        let input = include_str!("../fixtures/2020/day2/example.txt");
+
+        assert_eq!(
+            input_generator(input).unwrap(),
+            owned_generator(input).unwrap()
+        );
+    }
+
+    examples! {
+        day 2;
+        example: part_1, part_2;
+    }
 }
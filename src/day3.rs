@@ -1,7 +1,16 @@
+use crate::answer::Answer;
+use crate::grid::{Edge, Grid, Position};
+use crate::params::{self, value};
+use crate::parse::{Invalid, ParseError};
+use crate::render::Palette;
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Write};
+use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum Square {
+pub(crate) enum Square {
     Tree,
     Open,
 }
@@ -12,85 +21,199 @@ impl Square {
         self == Square::Tree
     }
 
-    pub fn from_char(c: char) -> Result<Self, ()> {
+    pub fn from_char(c: char) -> Result<Self, Invalid> {
         match c {
             '.' => Ok(Self::Open),
             '#' => Ok(Self::Tree),
-            _ => Err(()),
+            _ => Err(Invalid::new(
+                0,
+                format!("unexpected character `{}`, expected `.` or `#`", c),
+            )),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Slope {
+impl Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char(match self {
+            Square::Open => '.',
+            Square::Tree => '#',
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct Slope {
     right: usize,
     down: usize,
 }
 
-#[derive(Debug, Clone)]
-struct Map {
-    squares: Vec<Vec<Square>>,
+impl Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.right, self.down)
+    }
+}
+
+/// parses `<right>,<down>`, e.g. `3,1`.
+impl FromStr for Slope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (right, down) = s
+            .split_once(',')
+            .ok_or_else(|| "expected `<right>,<down>`".to_string())?;
+        let (right, down) = (value("right", right)?, value("down", down)?);
+
+        if down == 0 {
+            return Err("down must not be 0".to_string());
+        }
+
+        Ok(Slope { right, down })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Params {
+    /// slope of part 1
+    pub slope: Slope,
+    /// slopes whose tree counts are multiplied in part 2
+    pub slopes: Vec<Slope>,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        let slope = |right, down| Slope { right, down };
+
+        Params {
+            slope: slope(3, 1),
+            slopes: vec![
+                slope(1, 1),
+                slope(3, 1),
+                slope(5, 1),
+                slope(7, 1),
+                slope(1, 2),
+            ],
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+        match key {
+            "slope" => self.slope = value(key, s)?,
+            "slopes" => {
+                self.slopes = s
+                    .split_whitespace()
+                    .map(|slope| value(key, slope))
+                    .collect::<Result<_, _>>()?
+            }
+            _ => return Err(params::unknown(key, &self.values())),
+        }
+
+        Ok(())
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        let slopes: Vec<String> = self.slopes.iter().map(Slope::to_string).collect();
+
+        vec![
+            ("slope", self.slope.to_string()),
+            ("slopes", slopes.join(" ")),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Vec<String>", try_from = "Vec<String>")
+)]
+pub(crate) struct Map {
+    /// the map repeats to the right
+    squares: Grid<Square>,
 }
 
 impl Map {
+    pub fn new(squares: Grid<Square>) -> Self {
+        Self {
+            squares: squares.with_edges(Edge::Wrapping, Edge::Bounded),
+        }
+    }
+
     pub fn square_at(&self, x: usize, y: usize) -> Option<Square> {
-        self.squares
-            .get(y)
-            .map(|row| row.get(x % row.len()))
-            .map(|opt| opt.copied())
-            .flatten()
+        self.squares.get((x, y)).copied()
+    }
+
+    /// positions visited when travelling with the given slope, starting at the top left. The
+    /// positions continue to the right instead of wrapping around.
+    pub fn path(&self, slope: Slope) -> impl Iterator<Item = Position> + '_ {
+        (0..)
+            .map(move |step| (step * slope.right, step * slope.down))
+            .take_while(move |(_, y)| *y < self.squares.height())
     }
 
     /// "travels" over the map using the given slope and returns all squares that were visited.
     pub fn travel(&self, slope: Slope) -> Vec<Square> {
-        let (mut x, mut y) = (0, 0);
-        let mut visited = Vec::new();
+        self.path(slope)
+            .filter_map(|(x, y)| self.square_at(x, y))
+            .collect()
+    }
+}
 
-        while let Some(square) = self.square_at(x, y) {
-            visited.push(square);
+impl Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.squares.fmt(f)
+    }
+}
 
-            x += slope.right;
-            y += slope.down;
-        }
+/// rows of the map as in the input
+impl From<Map> for Vec<String> {
+    fn from(map: Map) -> Self {
+        map.to_string().lines().map(str::to_string).collect()
+    }
+}
+
+impl TryFrom<Vec<String>> for Map {
+    type Error = String;
 
-        visited
+    fn try_from(rows: Vec<String>) -> Result<Self, Self::Error> {
+        input_generator(&rows.join("\n")).map_err(|e| e.to_string())
     }
 }
 
-#[aoc_generator(day3)]
-fn input_generator(input: &str) -> Map {
-    let squares = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| Square::from_char(c).unwrap())
-                .collect()
-        })
-        .collect();
+#[cfg(feature = "serde")]
+impl From<&Map> for crate::export::Table {
+    fn from(map: &Map) -> Self {
+        let rows = map
+            .squares
+            .iter()
+            .map(|((x, y), square)| vec![y.to_string(), x.to_string(), square.to_string()])
+            .collect();
+
+        Self {
+            header: vec!["row", "column", "square"],
+            rows,
+        }
+    }
+}
 
-    Map { squares }
+#[aoc_generator(day3)]
+pub(crate) fn input_generator(input: &str) -> Result<Map, ParseError> {
+    Grid::parse(3, input, Square::from_char).map(Map::new)
 }
 
-#[aoc(day3, part1)]
-fn solve_part_1(input: &Map) -> usize {
+pub(crate) fn solve_part_1(input: &Map, params: &Params) -> usize {
     input
-        .travel(Slope { right: 3, down: 1 })
+        .travel(params.slope)
         .iter()
         .filter(|square| square.is_tree())
         .count()
 }
 
-#[aoc(day3, part2)]
-fn solve_part_2(input: &Map) -> usize {
-    let slopes = vec![
-        Slope { right: 1, down: 1 },
-        Slope { right: 3, down: 1 },
-        Slope { right: 5, down: 1 },
-        Slope { right: 7, down: 1 },
-        Slope { right: 1, down: 2 },
-    ];
-
-    slopes
+pub(crate) fn solve_part_2(input: &Map, params: &Params) -> usize {
+    params
+        .slopes
         .iter()
         .copied()
         .map(|slope| {
@@ -102,3 +225,139 @@ fn solve_part_2(input: &Map) -> usize {
         })
         .product()
 }
+
+/// colors of open squares, trees, the path and the trees hit on the path in rendered frames.
+pub(crate) fn palette() -> Palette {
+    "#f4f1e8,#2e7d32,#1e88e5,#d32f2f".parse().unwrap()
+}
+
+/// the map with the path of each slope drawn onto it, colored with the indices of [`palette`].
+/// The map repeats to the right as far as the longest path reaches, so every frame has the same
+/// size.
+pub(crate) fn frames(map: &Map, slopes: &[Slope]) -> Vec<Grid<u8>> {
+    let (width, height) = (map.squares.width().max(1), map.squares.height());
+    let reach = slopes
+        .iter()
+        .filter_map(|slope| map.path(*slope).last())
+        .map(|(x, _)| x + 1)
+        .max()
+        .unwrap_or(width);
+    let width = reach.div_ceil(width) * width;
+    let tiled = Grid::new(
+        width,
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|position| map.squares[position].is_tree() as u8)
+            .collect(),
+    );
+
+    slopes
+        .iter()
+        .map(|slope| {
+            let mut frame = tiled.clone();
+            for position in map.path(*slope) {
+                // open squares become path, trees become trees hit
+                frame[position] += 2;
+            }
+            frame
+        })
+        .collect()
+}
+
+#[aoc(day3, part1)]
+pub(crate) fn part_1(input: &Map) -> usize {
+    solve_part_1(input, &Params::default())
+}
+
+#[aoc(day3, part2)]
+pub(crate) fn part_2(input: &Map) -> usize {
+    solve_part_2(input, &Params::default())
+}
+
+#[derive(Default)]
+pub(crate) struct Day3 {
+    pub params: Params,
+}
+
+impl Solution for Day3 {
+    type Input<'a> = Map;
+
+    fn day(&self) -> u32 {
+        3
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input, &self.params).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input, &self.params).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day3::*;
+    use proptest::prelude::*;
+
+    fn square() -> impl Strategy<Value = Square> {
+        prop_oneof![Just(Square::Open), Just(Square::Tree)]
+    }
+
+    #[test]
+    fn test_display_reproduces_input() {
+        let input = include_str!("../fixtures/2020/day3/example.txt");
+
+        assert_eq!(input, input_generator(input).unwrap().to_string());
+    }
+
+    #[test]
+    fn test_frames() {
+        let map = input_generator(include_str!("../fixtures/2020/day3/example.txt")).unwrap();
+        let frames = frames(&map, &Params::default().slopes);
+
+        assert_eq!(5, frames.len());
+        // the map is 11 squares wide, 7,1 reaches square 70 on the last of its 11 rows
+        assert_eq!((77, 11), (frames[3].width(), frames[0].height()));
+        assert!(frames.iter().all(|frame| frame.width() == 77));
+        // 3,1 hits 7 trees
+        assert_eq!(
+            7,
+            frames[1]
+                .cells()
+                .iter()
+                .filter(|color| **color == 3)
+                .count()
+        );
+        assert_eq!(
+            11,
+            frames[1]
+                .cells()
+                .iter()
+                .filter(|color| **color >= 2)
+                .count()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_round_trip(
+            squares in (1..20usize).prop_flat_map(|width| {
+                prop::collection::vec(prop::collection::vec(square(), width), 1..20)
+            })
+        ) {
+            let map = Map::new(Grid::new(squares[0].len(), squares.concat()));
+
+            prop_assert_eq!(&map, &input_generator(&map.to_string()).unwrap());
+        }
+    }
+
+    examples! {
+        day 3;
+        example: part_1, part_2;
+    }
+}
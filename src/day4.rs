@@ -1,23 +1,31 @@
+use crate::answer::Answer;
+use crate::params::{self, format_range, range, value};
+use crate::parse::{self, Invalid, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
 
 mod validation {
     use regex::Regex;
     use std::collections::HashSet;
+    use std::ops::RangeInclusive;
 
     lazy_static! {
         static ref COLOR_PATTERN: Regex = Regex::new("^#[0-9a-f]{6}$").unwrap();
     }
 
-    pub fn is_number_between(input: &str, min: u32, max: u32) -> bool {
+    pub fn is_number_between(input: &str, range: &RangeInclusive<u32>) -> bool {
         match input.parse::<u32>() {
-            Ok(num) => num >= min && num <= max,
+            Ok(num) => range.contains(&num),
             Err(_) => false,
         }
     }
 
-    pub fn is_one_of(input: &str, options: HashSet<&str>) -> bool {
-        options.contains(&input)
+    pub fn is_one_of(input: &str, options: &HashSet<String>) -> bool {
+        options.contains(input)
     }
 
     pub fn is_color_code(input: &str) -> bool {
@@ -52,62 +60,130 @@ impl PropertyValidator {
     }
 }
 
-impl Default for PropertyValidator {
-    fn default() -> Self {
+impl From<&Params> for PropertyValidator {
+    fn from(params: &Params) -> Self {
         let mut validator = Self::new();
 
-        validator.add_rule("byr", |s| s.len() == 4);
-        validator.add_rule("byr", |s| validation::is_number_between(s, 1920, 2002));
-
-        validator.add_rule("iyr", |s| s.len() == 4);
-        validator.add_rule("iyr", |s| validation::is_number_between(s, 2010, 2020));
-
-        validator.add_rule("eyr", |s| s.len() == 4);
-        validator.add_rule("eyr", |s| validation::is_number_between(s, 2020, 2030));
+        let year = |validator: &mut Self, key, range: &RangeInclusive<u32>| {
+            let range = range.clone();
+            validator.add_rule(key, |s| s.len() == 4);
+            validator.add_rule(key, move |s| validation::is_number_between(s, &range));
+        };
+        year(&mut validator, "byr", &params.birth_year);
+        year(&mut validator, "iyr", &params.issue_year);
+        year(&mut validator, "eyr", &params.expiration_year);
 
-        validator.add_rule("hgt", |s| {
-            (s.ends_with("cm") && validation::is_number_between(&s[..s.len() - 2], 150, 193))
-                || (s.ends_with("in") && validation::is_number_between(&s[..s.len() - 2], 59, 76))
+        let (cm, inches) = (params.height_cm.clone(), params.height_in.clone());
+        validator.add_rule("hgt", move |s| {
+            (s.ends_with("cm") && validation::is_number_between(&s[..s.len() - 2], &cm))
+                || (s.ends_with("in") && validation::is_number_between(&s[..s.len() - 2], &inches))
         });
 
         validator.add_rule("hcl", validation::is_color_code);
 
-        validator.add_rule("ecl", |s| {
-            validation::is_one_of(s, {
-                let mut set = HashSet::new();
-                set.insert("amb");
-                set.insert("blu");
-                set.insert("brn");
-                set.insert("gry");
-                set.insert("hzl");
-                set.insert("oth");
-                set
-            })
-        });
+        let eye_colors: HashSet<String> = params.eye_colors.iter().cloned().collect();
+        validator.add_rule("ecl", move |s| validation::is_one_of(s, &eye_colors));
 
-        validator.add_rule("pid", |s| s.len() == 9);
-        validator.add_rule("pid", |s| s.parse::<u32>().is_ok());
+        let digits = params.passport_id_digits;
+        validator.add_rule("pid", move |s| s.len() == digits);
+        validator.add_rule("pid", |s| s.bytes().all(|b| b.is_ascii_digit()));
 
         validator
     }
 }
 
-#[derive(Debug)]
-struct Passport {
-    properties: HashMap<String, String>,
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Params {
+    /// keys every passport has to contain
+    pub required: Vec<String>,
+    pub birth_year: RangeInclusive<u32>,
+    pub issue_year: RangeInclusive<u32>,
+    pub expiration_year: RangeInclusive<u32>,
+    pub height_cm: RangeInclusive<u32>,
+    pub height_in: RangeInclusive<u32>,
+    pub eye_colors: Vec<String>,
+    pub passport_id_digits: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        let words = |s: &str| s.split(' ').map(str::to_string).collect();
+
+        Params {
+            required: words("byr iyr eyr hgt hcl ecl pid"),
+            birth_year: 1920..=2002,
+            issue_year: 2010..=2020,
+            expiration_year: 2020..=2030,
+            height_cm: 150..=193,
+            height_in: 59..=76,
+            eye_colors: words("amb blu brn gry grn hzl oth"),
+            passport_id_digits: 9,
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+        let words = |s: &str| s.split_whitespace().map(str::to_string).collect();
+
+        match key {
+            "required" => self.required = words(s),
+            "birth_year" => self.birth_year = range(key, s)?,
+            "issue_year" => self.issue_year = range(key, s)?,
+            "expiration_year" => self.expiration_year = range(key, s)?,
+            "height_cm" => self.height_cm = range(key, s)?,
+            "height_in" => self.height_in = range(key, s)?,
+            "eye_colors" => self.eye_colors = words(s),
+            "passport_id_digits" => self.passport_id_digits = value(key, s)?,
+            _ => return Err(params::unknown(key, &self.values())),
+        }
+
+        Ok(())
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("required", self.required.join(" ")),
+            ("birth_year", format_range(&self.birth_year)),
+            ("issue_year", format_range(&self.issue_year)),
+            ("expiration_year", format_range(&self.expiration_year)),
+            ("height_cm", format_range(&self.height_cm)),
+            ("height_in", format_range(&self.height_in)),
+            ("eye_colors", self.eye_colors.join(" ")),
+            ("passport_id_digits", self.passport_id_digits.to_string()),
+        ]
+    }
+}
+
+/// The properties are borrowed from the input text, but owned when deserialized, as json strings
+/// with escapes can't be borrowed.
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub(crate) struct Passport<'a> {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::export::sorted"))]
+    properties: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
-impl Passport {
-    fn contains_required_properties(&self) -> bool {
-        let required_keys = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+impl Passport<'_> {
+    fn into_owned(self) -> Passport<'static> {
+        let properties = self
+            .properties
+            .into_iter()
+            .map(|(key, value)| (Cow::Owned(key.into_owned()), Cow::Owned(value.into_owned())))
+            .collect();
 
+        Passport { properties }
+    }
+
+    fn contains_required_properties(&self, required_keys: &[String]) -> bool {
         required_keys
             .iter()
-            .all(|key| self.properties.contains_key(&key.to_string()))
+            .all(|key| self.properties.contains_key(key.as_str()))
     }
 
-    fn is_valid(&self, validator: &PropertyValidator) -> bool {
-        self.contains_required_properties()
+    fn is_valid(&self, required_keys: &[String], validator: &PropertyValidator) -> bool {
+        self.contains_required_properties(required_keys)
             && self
                 .properties
                 .iter()
@@ -115,47 +191,186 @@ impl Passport {
     }
 }
 
-#[aoc_generator(day4)]
-fn input_generator(input: &str) -> Vec<Passport> {
-    input
-        .split("\n\n")
-        .map(|multiline| {
-            let properties =
-                multiline
-                    .lines()
-                    .map(|line| line.trim())
-                    .fold(HashMap::new(), |mut map, line| {
-                        let parts: Vec<&str> = line.split(|c| c == ':' || c == ' ').collect();
-
-                        for pair in parts.chunks(2) {
-                            let key = pair.get(0).map(|s| s.to_string()).unwrap();
-                            let value = pair.get(1).map(|s| s.to_string()).unwrap();
-
-                            map.insert(key, value);
-                        }
-
-                        map
-                    });
-
-            Passport { properties }
+impl Display for Passport<'_> {
+    /// writes the properties on a single line, ordered by key so the output is deterministic.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut properties: Vec<_> = self.properties.iter().collect();
+        properties.sort_unstable();
+
+        let fields: Vec<String> = properties
+            .iter()
+            .map(|(key, value)| format!("{}:{}", key, value))
+            .collect();
+
+        f.write_str(&fields.join(" "))
+    }
+}
+
+/// only the known properties, the json export contains the others as well.
+#[cfg(feature = "serde")]
+impl crate::export::Record for Passport<'_> {
+    const HEADER: &'static [&'static str] =
+        &["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+    fn record(&self) -> Vec<String> {
+        Self::HEADER
+            .iter()
+            .map(|key| {
+                self.properties
+                    .get(*key)
+                    .map_or("", |value| value)
+                    .to_string()
+            })
+            .collect()
+    }
+}
+
+fn parse_passport(record: &str) -> Result<Passport<'_>, Invalid> {
+    let properties = record
+        .split_whitespace()
+        .map(|field| match field.split_once(':') {
+            Some((key, value)) => Ok((key.into(), value.into())),
+            None => Err(Invalid::at(record, field, "expected `<key>:<value>`")),
         })
-        .collect()
+        .collect::<Result<_, _>>()?;
+
+    Ok(Passport { properties })
 }
 
-#[aoc(day4, part1)]
-fn solve_part_1(input: &[Passport]) -> usize {
+pub(crate) fn input_generator(input: &str) -> Result<Vec<Passport<'_>>, ParseError> {
+    parse::records(4, input, parse_passport)
+}
+
+pub(crate) fn solve_part_1(input: &[Passport], params: &Params) -> usize {
     input
         .iter()
-        .filter(|pass| pass.contains_required_properties())
+        .filter(|pass| pass.contains_required_properties(&params.required))
         .count()
 }
 
-#[aoc(day4, part2)]
-fn solve_part_2(input: &[Passport]) -> usize {
-    let validator = PropertyValidator::default();
+pub(crate) fn solve_part_2(input: &[Passport], params: &Params) -> usize {
+    let validator = PropertyValidator::from(params);
 
     input
         .iter()
-        .filter(|pass| pass.is_valid(&validator))
+        .filter(|pass| pass.is_valid(&params.required, &validator))
         .count()
 }
+
+/// the passports with owned properties, cargo-aoc keeps the generated input next to the input
+/// text so it can't borrow from it.
+#[aoc_generator(day4)]
+pub(crate) fn owned_generator(input: &str) -> Result<Vec<Passport<'static>>, ParseError> {
+    Ok(input_generator(input)?
+        .into_iter()
+        .map(Passport::into_owned)
+        .collect())
+}
+
+#[aoc(day4, part1)]
+pub(crate) fn part_1(passports: &[Passport]) -> usize {
+    solve_part_1(passports, &Params::default())
+}
+
+#[aoc(day4, part2)]
+pub(crate) fn part_2(passports: &[Passport]) -> usize {
+    solve_part_2(passports, &Params::default())
+}
+
+#[derive(Default)]
+pub(crate) struct Day4 {
+    pub params: Params,
+}
+
+impl Solution for Day4 {
+    type Input<'a> = Vec<Passport<'a>>;
+
+    fn day(&self) -> u32 {
+        4
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input, &self.params).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input, &self.params).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day4::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_green_eyes_are_valid() {
+        let passport = |ecl: &str| {
+            format!(
+                "byr:1980 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:{} pid:087499704",
+                ecl
+            )
+        };
+        let valid =
+            |input: &str| solve_part_2(&input_generator(input).unwrap(), &Params::default());
+
+        assert_eq!(1, valid(&passport("grn")));
+        assert_eq!(0, valid(&passport("grr")));
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_round_trip(
+            properties in prop::collection::hash_map("[a-z]{3}", "[a-z0-9#:]{1,10}", 1..10)
+        ) {
+            let properties = properties
+                .iter()
+                .map(|(k, v)| (k.as_str().into(), v.as_str().into()))
+                .collect();
+            let passport = Passport { properties };
+
+            let text = passport.to_string();
+
+            prop_assert_eq!(&passport, &parse_passport(&text).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_passport_id_digits() {
+        let params = Params {
+            passport_id_digits: 10,
+            ..Params::default()
+        };
+        let passport = |pid| {
+            let record = format!(
+                "byr:1980 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:grn pid:{}",
+                pid
+            );
+            solve_part_2(&input_generator(&record).unwrap(), &params)
+        };
+
+        assert_eq!(1, passport("9999999999"));
+        assert_eq!(0, passport("+999999999"));
+    }
+
+    #[test]
+    fn test_owned_generator() {
+        let input = include_str!("../fixtures/2020/day4/example.txt");
+
+        assert_eq!(
+            input_generator(input).unwrap(),
+            owned_generator(input).unwrap()
+        );
+    }
+
+    examples! {
+        day 4;
+        example: part_1, part_2;
+        example_invalid: part_2;
+        example_valid: part_2;
+    }
+}
@@ -1,24 +1,24 @@
+use crate::answer::Answer;
+use crate::params::{self, value};
+use crate::parse::{self, Invalid, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashSet;
 use std::iter::FromIterator;
 
-#[derive(Copy, Clone, Debug)]
-struct BoardingPass {
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct BoardingPass {
     pub row: usize,
     pub seat: usize,
     pub id: usize,
 }
 
 impl BoardingPass {
-    pub fn decode(s: &str) -> Self {
-        let row = Self::decode_fragment(127, &s[..7]);
-        let seat = Self::decode_fragment(7, &s[7..]);
-        let id = row * 8 + seat;
+    /// the number encoded by `fragment`, in which `F` and `L` are the lower half.
+    fn decode_fragment(fragment: &str) -> usize {
+        let max = (1 << fragment.len()) - 1;
 
-        Self { row, seat, id }
-    }
-
-    fn decode_fragment(max: usize, fragment: &str) -> usize {
         fragment
             .chars()
             .enumerate()
@@ -27,23 +27,126 @@ impl BoardingPass {
                 _ => current,
             })
     }
+
+    /// row and seat of a pass made of `row_bits` characters of the row and `seat_bits` of the
+    /// seat.
+    fn decode(s: &str, params: &Params) -> Result<(usize, usize), Invalid> {
+        let len = params.row_bits + params.seat_bits;
+
+        if let Some((column, c)) = s.chars().enumerate().find(|(i, c)| match i {
+            i if *i < params.row_bits => !matches!(c, 'F' | 'B'),
+            _ => !matches!(c, 'L' | 'R'),
+        }) {
+            let expected = if column < params.row_bits {
+                "`F` or `B`"
+            } else {
+                "`L` or `R`"
+            };
+            return Err(Invalid::new(
+                column,
+                format!("unexpected character `{}`, expected {}", c, expected),
+            ));
+        }
+
+        if s.len() != len {
+            return Err(Invalid::new(
+                s.len().min(len),
+                format!("expected {} characters, found {}", len, s.len()),
+            ));
+        }
+
+        let (row, seat) = s.split_at(params.row_bits);
+        Ok((Self::decode_fragment(row), Self::decode_fragment(seat)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl crate::export::Record for BoardingPass {
+    const HEADER: &'static [&'static str] = &["row", "seat", "id"];
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.row.to_string(),
+            self.seat.to_string(),
+            self.id.to_string(),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Params {
+    /// characters of the row, which has `2^row_bits` rows
+    pub row_bits: usize,
+    /// characters of the seat, every row has `2^seat_bits` seats
+    pub seat_bits: usize,
+    /// factor of the row in the seat id, which is `row * id_factor + seat`
+    pub id_factor: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            row_bits: 7,
+            seat_bits: 3,
+            id_factor: 8,
+        }
+    }
+}
+
+/// parses the number of characters of the row or the seat.
+fn bits(key: &str, s: &str) -> Result<usize, String> {
+    match value(key, s)? {
+        bits if bits > 32 => Err(format!("{} {} is larger than 32", key, bits)),
+        bits => Ok(bits),
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+        match key {
+            "row_bits" => self.row_bits = bits(key, s)?,
+            "seat_bits" => self.seat_bits = bits(key, s)?,
+            "id_factor" => self.id_factor = value(key, s)?,
+            _ => return Err(params::unknown(key, &self.values())),
+        }
+
+        Ok(())
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("row_bits", self.row_bits.to_string()),
+            ("seat_bits", self.seat_bits.to_string()),
+            ("id_factor", self.id_factor.to_string()),
+        ]
+    }
+}
+
+/// parses the boarding passes, computing the seat ids using `params`.
+pub(crate) fn parse_passes(input: &str, params: &Params) -> Result<Vec<BoardingPass>, ParseError> {
+    parse::lines(5, input, |line| {
+        let (row, seat) = BoardingPass::decode(line, params)?;
+
+        Ok(BoardingPass {
+            row,
+            seat,
+            id: row * params.id_factor + seat,
+        })
+    })
 }
 
 #[aoc_generator(day5)]
-fn input_generator(input: &str) -> Vec<BoardingPass> {
-    input
-        .lines()
-        .map(|line| BoardingPass::decode(line))
-        .collect()
+pub(crate) fn input_generator(input: &str) -> Result<Vec<BoardingPass>, ParseError> {
+    parse_passes(input, &Params::default())
 }
 
 #[aoc(day5, part1)]
-fn solve_part_1(passes: &[BoardingPass]) -> usize {
+pub(crate) fn solve_part_1(passes: &[BoardingPass]) -> usize {
     passes.iter().map(|pass| pass.id).max().unwrap()
 }
 
 #[aoc(day5, part2)]
-fn solve_part_2(passes: &[BoardingPass]) -> usize {
+pub(crate) fn solve_part_2(passes: &[BoardingPass]) -> usize {
     let mut ids: Vec<usize> = passes.iter().map(|pass| pass.id).collect();
     ids.sort_unstable();
     let min = *ids.first().unwrap();
@@ -59,16 +162,41 @@ fn solve_part_2(passes: &[BoardingPass]) -> usize {
     panic!("no result found");
 }
 
+#[derive(Default)]
+pub(crate) struct Day5 {
+    pub params: Params,
+}
+
+impl Solution for Day5 {
+    type Input<'a> = Vec<BoardingPass>;
+
+    fn day(&self) -> u32 {
+        5
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        parse_passes(input, &self.params)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::day5::*;
 
     #[test]
     fn test_decode() {
-        let pass = BoardingPass::from_str("FBFBBFFRLR").unwrap();
+        let pass = input_generator("FBFBBFFRLR").unwrap()[0];
 
         assert_eq!(
-            Pass {
+            BoardingPass {
                 row: 44,
                 seat: 5,
                 id: 357
@@ -76,4 +204,47 @@ mod tests {
             pass
         );
     }
+
+    #[test]
+    fn test_id_factor() {
+        let params = Params {
+            id_factor: 10,
+            ..Params::default()
+        };
+        let passes = parse_passes("FBFBBFFRLR", &params).unwrap();
+
+        assert_eq!(445, passes[0].id);
+    }
+
+    #[test]
+    fn test_bits() {
+        let params = Params {
+            row_bits: 2,
+            seat_bits: 1,
+            id_factor: 2,
+        };
+        let passes = parse_passes("BFR\nFFL", &params).unwrap();
+
+        assert_eq!(
+            vec![
+                BoardingPass {
+                    row: 2,
+                    seat: 1,
+                    id: 5
+                },
+                BoardingPass {
+                    row: 0,
+                    seat: 0,
+                    id: 0
+                }
+            ],
+            passes
+        );
+        assert!(parse_passes("FBFBBFFRLR", &params).is_err());
+    }
+
+    examples! {
+        day 5;
+        example: part_1;
+    }
 }
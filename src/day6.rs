@@ -1,9 +1,14 @@
+use crate::answer::Answer;
+use crate::parse::{self, Invalid, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashMap;
 use std::str::FromStr;
 
-struct Group {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Group {
     group_size: usize,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::export::sorted"))]
     answers: HashMap<char, usize>,
 }
 
@@ -21,22 +26,43 @@ impl Group {
     }
 }
 
+/// the answers are written as `<question>:<count>` ordered by question, e.g. `a:3 b:1`.
+#[cfg(feature = "serde")]
+impl crate::export::Record for Group {
+    const HEADER: &'static [&'static str] = &["group_size", "answers"];
+
+    fn record(&self) -> Vec<String> {
+        let mut answers: Vec<(&char, &usize)> = self.answers.iter().collect();
+        answers.sort_unstable();
+
+        let answers: Vec<String> = answers
+            .iter()
+            .map(|(question, count)| format!("{}:{}", question, count))
+            .collect();
+
+        vec![self.group_size.to_string(), answers.join(" ")]
+    }
+}
+
 impl FromStr for Group {
-    type Err = ();
+    type Err = Invalid;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let answers = s
-            .chars()
-            .filter(|c| c.is_alphabetic())
-            .fold(HashMap::new(), |mut map, c| {
-                if let Some(count) = map.get_mut(&c) {
-                    *count += 1;
-                } else {
-                    map.insert(c, 1);
+        let mut answers = HashMap::new();
+
+        for (line, person) in s.lines().enumerate() {
+            for (column, c) in person.chars().enumerate() {
+                if !c.is_ascii_lowercase() {
+                    return Err(Invalid::new(
+                        column,
+                        format!("unexpected character `{}`, expected a question (a-z)", c),
+                    )
+                    .offset(line, 0));
                 }
 
-                map
-            });
+                *answers.entry(c).or_insert(0) += 1;
+            }
+        }
 
         Ok(Self {
             group_size: s.lines().count(),
@@ -46,23 +72,49 @@ impl FromStr for Group {
 }
 
 #[aoc_generator(day6)]
-fn input_generator(input: &str) -> Vec<Group> {
-    input
-        .split("\n\n")
-        .map(|multiline| multiline.parse::<Group>())
-        .collect::<Result<Vec<Group>, ()>>()
-        .unwrap()
+pub(crate) fn input_generator(input: &str) -> Result<Vec<Group>, ParseError> {
+    parse::records(6, input, str::parse)
 }
 
 #[aoc(day6, part1)]
-fn solve_part_1(groups: &[Group]) -> usize {
+pub(crate) fn solve_part_1(groups: &[Group]) -> usize {
     groups.iter().map(|group| group.questions_answered()).sum()
 }
 
 #[aoc(day6, part2)]
-fn solve_part_2(groups: &[Group]) -> usize {
+pub(crate) fn solve_part_2(groups: &[Group]) -> usize {
     groups
         .iter()
         .map(|group| group.questions_answered_by_all())
         .sum()
 }
+
+pub(crate) struct Day6;
+
+impl Solution for Day6 {
+    type Input<'a> = Vec<Group>;
+
+    fn day(&self) -> u32 {
+        6
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    examples! {
+        day 6;
+        example: part_1, part_2;
+    }
+}
@@ -1,77 +1,540 @@
+use crate::answer::Answer;
+use crate::params;
+use crate::parse::{self, Invalid, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::ops::Range;
 
-struct Rules {
-    rule_map: HashMap<String, HashMap<String, usize>>,
+/// index of a color in [`Rules::colors`].
+type Color = usize;
+
+/// The rules with every color interned, so the colors are borrowed from the input once and the
+/// bags refer to each other by index. Deserialized rules own their colors, as json strings with
+/// escapes can't be borrowed.
+#[derive(Debug, Default)]
+pub(crate) struct Rules<'a> {
+    /// name of every color
+    colors: Vec<Cow<'a, str>>,
+    ids: HashMap<Cow<'a, str>, Color>,
+    /// range of [`contents`](Rules::contents) of every color, `None` for colors without a rule
+    rules: Vec<Option<Range<usize>>>,
+    /// contents of all rules as color and count
+    contents: Vec<(Color, usize)>,
 }
 
-impl Rules {
-    pub fn bag_can_contain(&self, outer: &str, inner: &str) -> bool {
-        self.rule_map
-            .get(outer)
-            .map(|rules| {
-                rules
-                    .iter()
-                    .any(|(color, _count)| color == inner || self.bag_can_contain(color, inner))
-            })
-            .unwrap_or(false)
+impl<'a> Rules<'a> {
+    /// builds the rules from the contents of every color.
+    #[cfg(any(test, feature = "serde"))]
+    pub fn from_rules<R, C, S>(rules: R) -> Self
+    where
+        R: IntoIterator<Item = (S, C)>,
+        C: IntoIterator<Item = (S, usize)>,
+        S: Into<Cow<'a, str>>,
+    {
+        let mut result = Rules::default();
+        for (color, contents) in rules {
+            let contents = contents
+                .into_iter()
+                .map(|(content, count)| Ok((content.into(), count)));
+            let Ok(()) = result.add_rule::<std::convert::Infallible>(color.into(), contents);
+        }
+
+        result
+    }
+
+    fn into_owned(self) -> Rules<'static> {
+        let colors: Vec<Cow<'static, str>> = self
+            .colors
+            .into_iter()
+            .map(|color| Cow::Owned(color.into_owned()))
+            .collect();
+        let ids = colors
+            .iter()
+            .enumerate()
+            .map(|(id, color)| (color.clone(), id))
+            .collect();
+
+        Rules {
+            colors,
+            ids,
+            rules: self.rules,
+            contents: self.contents,
+        }
+    }
+
+    /// id of `color`, adding it if it is new.
+    fn intern(&mut self, color: Cow<'a, str>) -> Color {
+        if let Some(id) = self.id(&color) {
+            return id;
+        }
+
+        let id = self.colors.len();
+        self.ids.insert(color.clone(), id);
+        self.colors.push(color);
+        self.rules.push(None);
+        id
+    }
+
+    /// adds the rule for `color`, replacing an earlier one. Stops at the first invalid content.
+    fn add_rule<E>(
+        &mut self,
+        color: Cow<'a, str>,
+        contents: impl IntoIterator<Item = Result<(Cow<'a, str>, usize), E>>,
+    ) -> Result<(), E> {
+        let color = self.intern(color);
+        let start = self.contents.len();
+
+        for content in contents {
+            let (content, count) = content?;
+            let content = self.intern(content);
+            self.contents.push((content, count));
+        }
+        self.rules[color] = Some(start..self.contents.len());
+
+        Ok(())
+    }
+
+    fn id(&self, color: &str) -> Option<Color> {
+        self.ids.get(color).copied()
+    }
+
+    /// contents of a bag of `color`, empty for colors without a rule.
+    fn contents_of(&self, color: Color) -> &[(Color, usize)] {
+        match &self.rules[color] {
+            Some(range) => &self.contents[range.clone()],
+            None => &[],
+        }
+    }
+
+    /// every color ordered so the contents of a bag come before the bag, or the color and the
+    /// content of a rule which makes a bag contain itself.
+    fn order(&self) -> Result<Vec<Color>, (Color, Color)> {
+        #[derive(Copy, Clone, Eq, PartialEq)]
+        enum Mark {
+            New,
+            Open,
+            Done,
+        }
+
+        let mut marks = vec![Mark::New; self.colors.len()];
+        let mut order = Vec::with_capacity(self.colors.len());
+        // the open bags and the index of their next content, instead of recursing into the contents
+        let mut stack = Vec::new();
+
+        for root in 0..self.colors.len() {
+            if marks[root] != Mark::New {
+                continue;
+            }
+            marks[root] = Mark::Open;
+            stack.push((root, 0));
+
+            while let Some(&(color, index)) = stack.last() {
+                match self.contents_of(color).get(index) {
+                    Some(&(content, _)) => {
+                        stack.last_mut().unwrap().1 += 1;
+
+                        match marks[content] {
+                            Mark::New => {
+                                marks[content] = Mark::Open;
+                                stack.push((content, 0));
+                            }
+                            Mark::Open => return Err((color, content)),
+                            Mark::Done => {}
+                        }
+                    }
+                    None => {
+                        marks[color] = Mark::Done;
+                        order.push(color);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// whether a bag of each color contains a bag of `inner`, directly or nested.
+    ///
+    /// # Panics
+    ///
+    /// if a bag contains itself, which the parser and the deserializer reject.
+    pub fn can_contain(&self, inner: Color) -> Vec<bool> {
+        let mut can_contain = vec![false; self.colors.len()];
+        for color in self.order().expect("no bag contains itself") {
+            can_contain[color] = self
+                .contents_of(color)
+                .iter()
+                .any(|&(content, _)| content == inner || can_contain[content]);
+        }
+
+        can_contain
+    }
+
+    /// number of bags making up a bag of each color, including the bag itself.
+    ///
+    /// # Panics
+    ///
+    /// if a bag contains itself, which the parser and the deserializer reject.
+    pub fn bag_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.colors.len()];
+        for color in self.order().expect("no bag contains itself") {
+            counts[color] = self
+                .contents_of(color)
+                .iter()
+                .map(|&(content, count)| count * counts[content])
+                .sum::<usize>()
+                + 1;
+        }
+
+        counts
+    }
+
+    /// error for the rule of `color` containing `content`, which makes a bag contain itself.
+    fn cycle_error(&self, color: Color, content: Color) -> String {
+        match color == content {
+            true => format!("`{}` bags contain themselves", self.colors[color]),
+            false => format!(
+                "`{}` bags contain `{}` bags, which contain `{}` bags",
+                self.colors[color], self.colors[content], self.colors[color]
+            ),
+        }
     }
 
-    pub fn count_contained_bags(&self, color: &str) -> usize {
-        self.rule_map
-            .get(color)
-            .map(|rules| {
-                rules
+    /// the rules and their contents by name, ordered by color.
+    pub fn rule_map(&self) -> BTreeMap<&str, BTreeMap<&str, usize>> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter_map(|(color, range)| range.as_ref().map(|_| color))
+            .map(|color| {
+                let contents = self
+                    .contents_of(color)
                     .iter()
-                    .map(|(color, count)| count * self.count_contained_bags(color))
-                    .sum()
+                    .map(|&(content, count)| (&*self.colors[content], count))
+                    .collect();
+
+                (&*self.colors[color], contents)
             })
-            .unwrap_or(0)
-            + 1
+            .collect()
     }
 }
 
-#[aoc_generator(day7)]
-fn input_generator(input: &str) -> Rules {
-    let rule_map = input
+/// rules are equal if they have the same contents, regardless of the order colors were interned.
+impl PartialEq for Rules<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rule_map() == other.rule_map()
+    }
+}
+
+impl Eq for Rules<'_> {}
+
+impl Display for Rules<'_> {
+    /// writes a rule per line, ordered by color so the output is deterministic.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (color, contents) in self.rule_map() {
+            let contents: Vec<String> = contents
+                .into_iter()
+                .map(|(color, count)| match count {
+                    1 => format!("1 {} bag", color),
+                    count => format!("{} {} bags", count, color),
+                })
+                .collect();
+
+            match contents.as_slice() {
+                [] => writeln!(f, "{} bags contain no other bags.", color)?,
+                contents => writeln!(f, "{} bags contain {}.", color, contents.join(", "))?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// serializes the rules and their contents by name, ordered by color.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rules<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.rule_map().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Rules<'a> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rule_map =
+            BTreeMap::<Cow<'a, str>, BTreeMap<Cow<'a, str>, usize>>::deserialize(deserializer)?;
+
+        let rules = Rules::from_rules(rule_map);
+        match rules.order() {
+            Ok(_) => Ok(rules),
+            Err((color, content)) => {
+                Err(serde::de::Error::custom(rules.cycle_error(color, content)))
+            }
+        }
+    }
+}
+
+/// a row per bag and content, bags without content get a single row without content.
+#[cfg(feature = "serde")]
+impl From<&Rules<'_>> for crate::export::Table {
+    fn from(rules: &Rules<'_>) -> Self {
+        let mut rows = Vec::new();
+        for (color, contents) in rules.rule_map() {
+            if contents.is_empty() {
+                rows.push(vec![color.to_string(), String::new(), "0".to_string()]);
+            }
+            for (content, count) in contents {
+                rows.push(vec![
+                    color.to_string(),
+                    content.to_string(),
+                    count.to_string(),
+                ]);
+            }
+        }
+
+        Self {
+            header: vec!["color", "content", "count"],
+            rows,
+        }
+    }
+}
+
+fn parse_content<'a>(line: &str, content: &'a str) -> Result<(Cow<'a, str>, usize), Invalid> {
+    let (count, color) = content
+        .split_once(' ')
+        .ok_or_else(|| Invalid::at(line, content, "expected `<count> <color> bags`"))?;
+    let color = color
+        .strip_suffix(" bags")
+        .or_else(|| color.strip_suffix(" bag"))
+        .ok_or_else(|| Invalid::at(line, content, "expected `<count> <color> bags`"))?;
+
+    Ok((color.into(), parse::number(line, count)?))
+}
+
+/// parses a rule and adds it to `rules`.
+fn parse_rule<'a>(rules: &mut Rules<'a>, line: &'a str) -> Result<(), Invalid> {
+    let rule = line
+        .strip_suffix('.')
+        .ok_or_else(|| Invalid::new(line.chars().count(), "expected `.` at the end of the rule"))?;
+    let (color, contents) = rule
+        .split_once(" bags contain ")
+        .ok_or_else(|| Invalid::at(line, rule, "expected `<color> bags contain <contents>`"))?;
+
+    let contents = match contents {
+        "no other bags" => None,
+        s => Some(s.split(", ").map(|content| parse_content(line, content))),
+    };
+
+    rules.add_rule(color.into(), contents.into_iter().flatten())
+}
+
+pub(crate) fn input_generator(input: &str) -> Result<Rules<'_>, ParseError> {
+    let mut rules = Rules::default();
+    parse::lines(7, input, |line| parse_rule(&mut rules, line))?;
+
+    match rules.order() {
+        Ok(_) => Ok(rules),
+        Err((color, content)) => Err(locate_cycle(&rules, input, color, content)),
+    }
+}
+
+/// error pointing to the content `content` in the rule of `color`.
+fn locate_cycle(rules: &Rules, input: &str, color: Color, content: Color) -> ParseError {
+    // later rules of a color replace earlier ones, so the cycle is in the last one
+    let prefix = format!("{} bags contain ", rules.colors[color]);
+    let (index, line) = input
         .lines()
-        .map(|line| &line[..line.len() - 1])
-        .map(|line| {
-            let parts: Vec<&str> = line.split("bags contain").map(|p| p.trim()).collect();
-            let color = parts.get(0).unwrap().to_string();
-
-            let content = match *parts.get(1).unwrap() {
-                "no other bags" => HashMap::new(),
-                s => s
-                    .split(',')
-                    .map(|p| p.trim())
-                    .map(|s| {
-                        let parts: Vec<&str> = s.split_whitespace().map(|p| p.trim()).collect();
-                        let count = parts.get(0).unwrap().parse::<usize>().unwrap();
-                        let color: String = parts[1..=2].join(" ");
-
-                        (color, count)
-                    })
-                    .collect(),
-            };
-
-            (color, content)
+        .enumerate()
+        .filter(|(_, line)| line.starts_with(&prefix))
+        .last()
+        .expect("every rule has a line");
+    let fragment = line[prefix.len()..]
+        .trim_end_matches('.')
+        .split(", ")
+        .find(|fragment| {
+            matches!(parse_content(line, fragment), Ok((name, _)) if name == rules.colors[content])
         })
-        .collect();
+        .unwrap_or(line);
+
+    Invalid::at(line, fragment, rules.cycle_error(color, content)).locate(7, index, line)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Params {
+    /// color of my bag
+    pub bag: String,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            bag: "shiny gold".to_string(),
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+        match key {
+            "bag" => self.bag = s.trim().to_string(),
+            _ => return Err(params::unknown(key, &self.values())),
+        }
+
+        Ok(())
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![("bag", self.bag.clone())]
+    }
+}
 
-    Rules { rule_map }
+pub(crate) fn solve_part_1(rules: &Rules, params: &Params) -> usize {
+    match rules.id(&params.bag) {
+        Some(bag) => rules
+            .can_contain(bag)
+            .into_iter()
+            .filter(|can_contain| *can_contain)
+            .count(),
+        None => 0,
+    }
+}
+
+pub(crate) fn solve_part_2(rules: &Rules, params: &Params) -> usize {
+    match rules.id(&params.bag) {
+        Some(bag) => rules.bag_counts()[bag] - 1,
+        None => 0,
+    }
+}
+
+/// the rules with owned colors, cargo-aoc keeps the generated input next to the input text so it
+/// can't borrow from it.
+#[aoc_generator(day7)]
+pub(crate) fn owned_generator(input: &str) -> Result<Rules<'static>, ParseError> {
+    input_generator(input).map(Rules::into_owned)
 }
 
 #[aoc(day7, part1)]
-fn solve_part_1(rules: &Rules) -> usize {
-    rules
-        .rule_map
-        .keys()
-        .filter(|color| rules.bag_can_contain(color, "shiny gold"))
-        .count()
+pub(crate) fn part_1(rules: &Rules) -> usize {
+    solve_part_1(rules, &Params::default())
 }
 
 #[aoc(day7, part2)]
-fn solve_part_2(rules: &Rules) -> usize {
-    rules.count_contained_bags("shiny gold") - 1
+pub(crate) fn part_2(rules: &Rules) -> usize {
+    solve_part_2(rules, &Params::default())
+}
+
+#[derive(Default)]
+pub(crate) struct Day7 {
+    pub params: Params,
+}
+
+impl Solution for Day7 {
+    type Input<'a> = Rules<'a>;
+
+    fn day(&self) -> u32 {
+        7
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input, &self.params).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input, &self.params).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day7::*;
+    use proptest::prelude::*;
+
+    const COLOR: &str = "[a-z]{1,6} [a-z]{1,6}";
+
+    #[test]
+    fn test_display_reproduces_sorted_input() {
+        let input = "a b bags contain 1 c d bag, 2 e f bags.\nc d bags contain no other bags.\n";
+
+        assert_eq!(input, input_generator(input).unwrap().to_string());
+    }
+
+    #[test]
+    fn test_cycles_are_rejected() {
+        let error = input_generator(
+            "a b bags contain 1 c d bag.\nc d bags contain 2 e f bags, 1 a b bag.\ne f bags contain no other bags.",
+        )
+        .unwrap_err();
+
+        assert_eq!((2, 30), (error.line(), error.column()));
+        assert_eq!(
+            "`c d` bags contain `a b` bags, which contain `c d` bags",
+            error.message()
+        );
+        assert_eq!(
+            "`a b` bags contain themselves",
+            input_generator("a b bags contain 1 a b bag.")
+                .unwrap_err()
+                .message()
+        );
+    }
+
+    #[test]
+    fn test_deep_rules() {
+        let depth = 100_000;
+        let input: Vec<String> = (0..depth)
+            .map(|level| format!("c {} bags contain 1 c {} bag.", level, level + 1))
+            .collect();
+        let input = input.join("\n");
+        let rules = input_generator(&input).unwrap();
+        let params = Params {
+            bag: format!("c {}", depth),
+        };
+
+        assert_eq!(depth, solve_part_1(&rules, &params));
+        assert_eq!(depth, solve_part_2(&rules, &Params { bag: "c 0".into() }));
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_round_trip(
+            rule_map in prop::collection::hash_map(
+                COLOR,
+                prop::collection::hash_map(COLOR, 1..10usize, 0..4),
+                1..10,
+            )
+        ) {
+            let rules = Rules::from_rules(rule_map.iter().map(|(color, contents)| {
+                (color.as_str(), contents.iter().map(|(color, count)| (color.as_str(), *count)))
+            }));
+            prop_assume!(rules.order().is_ok());
+
+            let text = rules.to_string();
+
+            prop_assert_eq!(&rules, &input_generator(&text).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_owned_generator() {
+        let input = include_str!("../fixtures/2020/day7/example1.txt");
+
+        assert_eq!(
+            input_generator(input).unwrap(),
+            owned_generator(input).unwrap()
+        );
+    }
+
+    examples! {
+        day 7;
+        example1: part_1, part_2;
+        example2: part_2;
+    }
 }
@@ -1,31 +1,73 @@
+use crate::answer::Answer;
+use crate::cancel;
+use crate::parse::{self, Invalid, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashSet;
+use std::fmt::{self, Display};
 use std::str::FromStr;
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Instruction {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum Instruction {
     Acc(i32),
     Jmp(i32),
-    Nop,
+    Nop(i32),
 }
 
 impl FromStr for Instruction {
-    type Err = ();
+    type Err = Invalid;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let instruction = &s[..=2];
-        let value = s[4..].parse::<i32>().map_err(|_| ())?;
+        let (instruction, value) = s
+            .split_once(' ')
+            .ok_or_else(|| Invalid::at(s, s, "expected `<operation> <argument>`"))?;
+        let value = parse::number::<i32>(s, value)?;
 
         match instruction {
             "acc" => Ok(Instruction::Acc(value)),
             "jmp" => Ok(Instruction::Jmp(value)),
-            "nop" => Ok(Instruction::Nop),
-            _ => Err(()),
+            "nop" => Ok(Instruction::Nop(value)),
+            _ => Err(Invalid::at(
+                s,
+                instruction,
+                format!(
+                    "unknown operation `{}`, expected `acc`, `jmp` or `nop`",
+                    instruction
+                ),
+            )),
         }
     }
 }
 
-struct Vm {
+#[cfg(feature = "serde")]
+impl crate::export::Record for Instruction {
+    const HEADER: &'static [&'static str] = &["operation", "argument"];
+
+    fn record(&self) -> Vec<String> {
+        let (operation, argument) = match self {
+            Instruction::Acc(value) => ("acc", value),
+            Instruction::Jmp(value) => ("jmp", value),
+            Instruction::Nop(value) => ("nop", value),
+        };
+
+        vec![operation.to_string(), argument.to_string()]
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Acc(value) => write!(f, "acc {:+}", value),
+            Instruction::Jmp(value) => write!(f, "jmp {:+}", value),
+            Instruction::Nop(value) => write!(f, "nop {:+}", value),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Vm {
     instructions: Vec<Instruction>,
     ptr: usize,
     state: i32,
@@ -58,7 +100,7 @@ impl Vm {
                 self.ptr = (self.ptr as i32 + offset) as usize;
                 return;
             }
-            Instruction::Nop => {}
+            Instruction::Nop(_) => {}
         }
 
         self.ptr += 1;
@@ -71,6 +113,8 @@ impl Vm {
         let mut executed = HashSet::with_capacity(self.instructions.len());
 
         while let Some(&instruction) = self.instructions.get(self.ptr) {
+            cancel::checkpoint();
+
             if executed.contains(&self.ptr) && breaker(instruction) {
                 return Err(self.state);
             }
@@ -83,32 +127,49 @@ impl Vm {
     }
 }
 
+/// the instructions and the state of the vm where part 1 stops, before an instruction runs the
+/// second time. Programs which terminate stop after the last instruction.
+#[cfg(feature = "serde")]
+pub(crate) fn halt_on_loop(instructions: &[Instruction]) -> Vm {
+    let mut vm = Vm::new(instructions.to_vec());
+    let _ = vm.run_with_breaker(Box::new(|_| true));
+    vm
+}
+
+/// the instructions only, the state of the vm isn't part of an instruction.
+#[cfg(feature = "serde")]
+impl From<&Vm> for crate::export::Table {
+    fn from(vm: &Vm) -> Self {
+        Self::of(&vm.instructions)
+    }
+}
+
 #[aoc_generator(day8)]
-fn input_generator(input: &str) -> Vec<Instruction> {
-    input
-        .lines()
-        .map(|line| line.parse::<Instruction>().unwrap())
-        .collect()
+pub(crate) fn input_generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse::lines(8, input, str::parse)
 }
 
 #[aoc(day8, part1)]
-fn solve_part_1(instructions: &[Instruction]) -> i32 {
+pub(crate) fn solve_part_1(instructions: &[Instruction]) -> i32 {
     let mut vm = Vm::new(instructions.to_vec());
     vm.run_with_breaker(Box::new(|_| true)).unwrap_err()
 }
 
 #[aoc(day8, part2)]
-fn solve_part_2(instructions: &[Instruction]) -> i32 {
-    let jump_indices: Vec<usize> = instructions
+pub(crate) fn solve_part_2(instructions: &[Instruction]) -> i32 {
+    let patches: Vec<(usize, Instruction)> = instructions
         .iter()
         .enumerate()
-        .filter(|(_, i)| matches!(i, Instruction::Jmp(_)))
-        .map(|(index, _)| index)
+        .filter_map(|(index, instruction)| match *instruction {
+            Instruction::Jmp(value) => Some((index, Instruction::Nop(value))),
+            Instruction::Nop(value) => Some((index, Instruction::Jmp(value))),
+            Instruction::Acc(_) => None,
+        })
         .collect();
 
-    for jump in jump_indices {
+    for (index, patch) in patches {
         let mut vm = Vm::new(instructions.to_vec());
-        vm.patch(jump, Instruction::Nop);
+        vm.patch(index, patch);
 
         let result = vm.run_with_breaker(Box::new(|inst| matches!(inst, Instruction::Jmp(_))));
 
@@ -120,3 +181,81 @@ fn solve_part_2(instructions: &[Instruction]) -> i32 {
 
     panic!("no result found");
 }
+
+pub(crate) struct Day8;
+
+impl Solution for Day8 {
+    type Input<'a> = Vec<Instruction>;
+
+    fn day(&self) -> u32 {
+        8
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancel::{self, Cancelled, Token};
+    use crate::day8::*;
+    use proptest::prelude::*;
+    use std::panic;
+
+    #[test]
+    fn test_patching_nop_to_jmp() {
+        let program = input_generator("acc +1\nnop +3\njmp -2\njmp -1\nacc +5").unwrap();
+
+        assert_eq!(1, solve_part_1(&program));
+        assert_eq!(6, solve_part_2(&program));
+    }
+
+    #[test]
+    fn test_endless_loop_can_be_cancelled() {
+        let token = Token::new();
+        token.cancel();
+
+        let result = panic::catch_unwind(|| {
+            let mut vm = Vm::new(vec![Instruction::Jmp(0)]);
+            cancel::with_token(&token, || vm.run_with_breaker(Box::new(|_| false)))
+        });
+
+        assert!(result.unwrap_err().is::<Cancelled>());
+    }
+
+    #[test]
+    fn test_display_reproduces_input() {
+        let input = include_str!("../fixtures/2020/day8/example.txt");
+        let instructions = input_generator(input).unwrap();
+        let lines: Vec<String> = instructions.iter().map(ToString::to_string).collect();
+
+        assert_eq!(input, lines.join("\n") + "\n");
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_round_trip(value: i32, operation in 0..3) {
+            let instruction = match operation {
+                0 => Instruction::Acc(value),
+                1 => Instruction::Jmp(value),
+                _ => Instruction::Nop(value),
+            };
+
+            prop_assert_eq!(instruction, instruction.to_string().parse().unwrap());
+        }
+    }
+
+    examples! {
+        day 8;
+        example: part_1, part_2;
+    }
+}
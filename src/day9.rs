@@ -1,14 +1,45 @@
+use crate::answer::Answer;
+use crate::params::{self, value};
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 
 #[aoc_generator(day9)]
-fn input_generator(input: &str) -> Vec<u64> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+pub(crate) fn input_generator(input: &str) -> Result<Vec<u64>, ParseError> {
+    parse::lines(9, input, |line| parse::number(line, line))
 }
 
-fn find_invalid(numbers: &[u64]) -> Option<u64> {
-    for (index, number) in numbers.iter().copied().enumerate().skip(25) {
-        let valid = numbers[(index - 25)..index]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Params {
+    /// number of previous numbers every number has to be the sum of two of
+    pub preamble: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { preamble: 25 }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+        match key {
+            "preamble" => self.preamble = value(key, s)?,
+            _ => return Err(params::unknown(key, &self.values())),
+        }
+
+        Ok(())
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![("preamble", self.preamble.to_string())]
+    }
+}
+
+fn find_invalid(numbers: &[u64], preamble: usize) -> Option<u64> {
+    for (index, number) in numbers.iter().copied().enumerate().skip(preamble) {
+        let valid = numbers[(index - preamble)..index]
             .iter()
             .tuple_combinations()
             .any(|(a, b)| a + b == number);
@@ -21,37 +52,129 @@ fn find_invalid(numbers: &[u64]) -> Option<u64> {
     None
 }
 
+/// first range of at least two numbers that sums up to `target_sum`, as start and exclusive end.
 fn find_contiguous_group(numbers: &[u64], target_sum: u64) -> Option<(usize, usize)> {
     let (mut group_start, mut group_end, mut sum) = (0, 0, 0);
 
-    while group_end < numbers.len() {
-        if sum == target_sum {
+    loop {
+        let length = group_end - group_start;
+
+        if sum == target_sum && length >= 2 {
             return Some((group_start, group_end));
         }
 
-        if sum > target_sum {
+        if (sum < target_sum || length < 2) && group_end < numbers.len() {
+            sum += numbers[group_end];
+            group_end += 1;
+        } else if sum > target_sum {
             sum -= numbers[group_start];
             group_start += 1;
         } else {
-            sum += numbers[group_end];
-            group_end += 1;
+            return None;
         }
     }
+}
 
-    None
+/// sum of the smallest and largest number of the group adding up to the invalid number.
+fn find_weakness(numbers: &[u64], preamble: usize) -> u64 {
+    let invalid_number = find_invalid(numbers, preamble).unwrap();
+    let (group_start, group_end) = find_contiguous_group(numbers, invalid_number).unwrap();
+    let mut group = numbers[group_start..group_end].to_vec();
+    group.sort_unstable();
+
+    group.first().unwrap() + group.last().unwrap()
+}
+
+pub(crate) fn solve_part_1(numbers: &[u64], params: &Params) -> u64 {
+    find_invalid(numbers, params.preamble).unwrap()
+}
+
+pub(crate) fn solve_part_2(numbers: &[u64], params: &Params) -> u64 {
+    find_weakness(numbers, params.preamble)
 }
 
 #[aoc(day9, part1)]
-fn solve_part_1(numbers: &[u64]) -> u64 {
-    find_invalid(numbers).unwrap()
+pub(crate) fn part_1(numbers: &[u64]) -> u64 {
+    solve_part_1(numbers, &Params::default())
 }
 
 #[aoc(day9, part2)]
-fn solve_part_2(numbers: &[u64]) -> u64 {
-    let invalid_number = find_invalid(numbers).unwrap();
-    let (group_start, group_end) = find_contiguous_group(numbers, invalid_number).unwrap();
-    let mut group = numbers[group_start..=group_end].to_vec();
-    group.sort_unstable();
+pub(crate) fn part_2(numbers: &[u64]) -> u64 {
+    solve_part_2(numbers, &Params::default())
+}
 
-    group.first().unwrap() + group.last().unwrap()
+#[derive(Default)]
+pub(crate) struct Day9 {
+    pub params: Params,
+}
+
+impl Solution for Day9 {
+    type Input<'a> = Vec<u64>;
+
+    fn day(&self) -> u32 {
+        9
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_1(input, &self.params).into()
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer {
+        solve_part_2(input, &self.params).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day9::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_contiguous_group_bounds() {
+        // 100 is not a sum of two of 1..=25, and 9..=16 is the first run summing to it
+        let numbers: Vec<u64> = (1..=25).chain(Some(100)).collect();
+
+        assert_eq!(100, solve_part_1(&numbers, &Params::default()));
+        assert_eq!(9 + 16, solve_part_2(&numbers, &Params::default()));
+    }
+
+    fn find_contiguous_group_naive(numbers: &[u64], target_sum: u64) -> Option<(usize, usize)> {
+        (0..numbers.len()).find_map(|start| {
+            (start + 2..=numbers.len())
+                .find(|end| numbers[start..*end].iter().sum::<u64>() == target_sum)
+                .map(|end| (start, end))
+        })
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(None, find_contiguous_group(&[], 0));
+        assert_eq!(None, find_contiguous_group(&[5], 5));
+        assert_eq!(Some((0, 2)), find_contiguous_group(&[0, 0], 0));
+        assert_eq!(Some((1, 3)), find_contiguous_group(&[7, 2, 5], 7));
+        assert_eq!(Some((2, 4)), find_contiguous_group(&[9, 1, 3, 4], 7));
+    }
+
+    proptest! {
+        #[test]
+        fn test_find_contiguous_group_matches_naive(
+            numbers in prop::collection::vec(0..20u64, 0..20),
+            target_sum in 0..60u64,
+        ) {
+            prop_assert_eq!(
+                find_contiguous_group_naive(&numbers, target_sum),
+                find_contiguous_group(&numbers, target_sum)
+            );
+        }
+    }
+
+    // the example uses a preamble of 5 numbers instead of 25
+    examples! {
+        day 9, params "9.preamble = 5";
+        example: part_1, part_2;
+    }
 }
@@ -0,0 +1,117 @@
+//! Tests for the examples of the puzzle descriptions.
+//!
+//! The examples live in `fixtures/<year>/day<day>/<name>.txt`, next to `<name>.answers` which holds
+//! the expected answer of every part the example is given for:
+//!
+//! ```text
+//! # part answer
+//! 1 514579
+//! 2 241861950
+//! ```
+//!
+//! [`examples!`] generates a test per example and part, e.g. `day1::tests::example::part_1`.
+
+use crate::answer::Answer;
+use crate::params::Overrides;
+use crate::runner::YEAR;
+use crate::Registry;
+use std::fs;
+use std::path::PathBuf;
+
+/// generates a test for every listed part of every listed example of `day`.
+///
+/// The examples are solved by the registered solution of the day, with the parameters overridden
+/// by `params` (in the format of an override file) if the example uses other ones than the puzzle.
+/// A `solve` function called with the part and the input can be given instead.
+///
+/// ```ignore
+/// examples! {
+///     day 10;
+///     example1: part_1, part_2;
+///     example2: part_1, part_2;
+/// }
+///
+/// examples! {
+///     day 9, params "9.preamble = 5";
+///     example: part_1, part_2;
+/// }
+/// ```
+macro_rules! examples {
+    (day $day:literal; $($name:ident: $($part:ident),+;)+) => {
+        examples! {
+            day $day, params "";
+            $($name: $($part),+;)+
+        }
+    };
+    (day $day:literal, params $params:literal; $($name:ident: $($part:ident),+;)+) => {
+        examples! {
+            day $day, solve |part, input| crate::examples::solve($params, $day, part, input);
+            $($name: $($part),+;)+
+        }
+    };
+    (day $day:literal, solve $solve:expr; $($name:ident: $($part:ident),+;)+) => {
+        $(
+            mod $name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                $(
+                    #[test]
+                    fn $part() {
+                        crate::examples::check($day, stringify!($name), stringify!($part), $solve);
+                    }
+                )+
+            }
+        )+
+    };
+}
+
+fn path(day: u32, file: &str) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "fixtures",
+        &YEAR.to_string(),
+        &format!("day{}", day),
+        file,
+    ]
+    .iter()
+    .collect()
+}
+
+/// solves `part` of `day` using the registered solution, with the parameters overridden by
+/// `params`.
+pub(crate) fn solve(params: &str, day: u32, part: u32, input: &str) -> Answer {
+    let overrides: Overrides = params.parse().unwrap_or_else(|e| panic!("{}", e));
+
+    Registry::with_overrides(&overrides)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .get(day, part)
+        .unwrap_or_else(|| panic!("no solution for day {} part {}", day, part))
+        .solve(input)
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// solves the example `name` of `day` and compares the answer of `part` (e.g. `part_1`) with the
+/// expected one.
+pub(crate) fn check(day: u32, name: &str, part: &str, solve: impl Fn(u32, &str) -> Answer) {
+    let part: u32 = part
+        .trim_start_matches("part_")
+        .parse()
+        .unwrap_or_else(|_| panic!("`{}` isn't a part, expected e.g. `part_1`", part));
+    let read = |file: String| {
+        let path = path(day, &file);
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+    };
+
+    let input = read(format!("{}.txt", name));
+    let expected = read(format!("{}.answers", name))
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once(' '))
+        .find(|(p, _)| p.parse() == Ok(part))
+        .map(|(_, answer)| answer.trim().to_string())
+        .unwrap_or_else(|| panic!("{}.answers has no answer for part {}", name, part));
+    let Ok(expected) = expected.parse::<Answer>();
+
+    assert_eq!(expected, solve(part, &input));
+}
@@ -0,0 +1,303 @@
+//! Exports the parsed puzzle inputs as JSON or CSV, e.g. to analyse them with other tools.
+//!
+//! JSON contains everything the solutions know about the input and can be deserialized again, for
+//! day 8 also the state of the vm where part 1 stops. CSV is a flat table per day with one row per
+//! item of the input, like a passport or an instruction. Nested data is spread over several rows
+//! (the contents of a bag) or cells (the answers of a group).
+
+use crate::parse::ParseError;
+use crate::{day1, day10, day11, day2, day3, day4, day5, day6, day7, day8, day9};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format `{}`, expected `json` or `csv`", s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Parse(ParseError),
+    UnknownDay(u32),
+    Json(serde_json::Error),
+    Csv(csv::Error),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Parse(e) => write!(f, "failed to parse the input:\n{}", e),
+            ExportError::UnknownDay(day) => write!(f, "no input format for day {}", day),
+            ExportError::Json(e) => write!(f, "failed to write json: {}", e),
+            ExportError::Csv(e) => write!(f, "failed to write csv: {}", e),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+impl From<ParseError> for ExportError {
+    fn from(e: ParseError) -> Self {
+        ExportError::Parse(e)
+    }
+}
+
+/// serializes `map` ordered by key, so exports of the same input are identical.
+pub(crate) fn sorted<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// Flat representation of an input for CSV.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Table {
+    pub header: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// table with a row per item.
+    pub(crate) fn of<T: Record>(items: &[T]) -> Self {
+        Table {
+            header: T::HEADER.to_vec(),
+            rows: items.iter().map(Record::record).collect(),
+        }
+    }
+
+    pub fn to_csv(&self) -> Result<String, csv::Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(&self.header)?;
+        for row in &self.rows {
+            writer.write_record(row)?;
+        }
+
+        let csv = writer.into_inner().map_err(|e| e.into_error())?;
+        Ok(String::from_utf8(csv).expect("the records are strings"))
+    }
+}
+
+/// Item of an input which can be written as a single row of a [`Table`].
+pub(crate) trait Record {
+    const HEADER: &'static [&'static str];
+
+    fn record(&self) -> Vec<String>;
+}
+
+impl Record for u32 {
+    const HEADER: &'static [&'static str] = &["value"];
+
+    fn record(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+impl Record for u64 {
+    const HEADER: &'static [&'static str] = &["value"];
+
+    fn record(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+fn write<T>(
+    value: &T,
+    table: impl FnOnce(&T) -> Table,
+    format: Format,
+) -> Result<String, ExportError>
+where
+    T: Serialize + ?Sized,
+{
+    match format {
+        Format::Json => serde_json::to_string_pretty(value).map_err(ExportError::Json),
+        Format::Csv => table(value).to_csv().map_err(ExportError::Csv),
+    }
+}
+
+/// parses `input` of `day` and writes it in `format`.
+pub fn export(day: u32, input: &str, format: Format) -> Result<String, ExportError> {
+    let input = &*crate::parse::normalize(input);
+
+    match day {
+        1 => write(&day1::input_generator(input)?, |v| Table::of(v), format),
+        2 => write(&day2::input_generator(input)?, |v| Table::of(v), format),
+        3 => write(&day3::input_generator(input)?, |v| Table::from(v), format),
+        4 => write(&day4::input_generator(input)?, |v| Table::of(v), format),
+        5 => write(&day5::input_generator(input)?, |v| Table::of(v), format),
+        6 => write(&day6::input_generator(input)?, |v| Table::of(v), format),
+        7 => write(&day7::input_generator(input)?, |v| Table::from(v), format),
+        8 => {
+            let vm = day8::halt_on_loop(&day8::input_generator(input)?);
+            write(&vm, |v| Table::from(v), format)
+        }
+        9 => write(&day9::input_generator(input)?, |v| Table::of(v), format),
+        10 => write(&day10::input_generator(input)?, |v| Table::of(v), format),
+        11 => {
+            let ferry = day11::Ferry::new(day11::input_generator(input)?);
+            write(&ferry, |v| Table::from(v), format)
+        }
+        day => Err(ExportError::UnknownDay(day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::*;
+    use serde::Deserialize;
+    use std::fmt::Debug;
+
+    fn example(day: u32, name: &str) -> String {
+        let path = format!(
+            "{}/fixtures/2020/day{}/{}.txt",
+            env!("CARGO_MANIFEST_DIR"),
+            day,
+            name
+        );
+
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    fn json(day: u32, name: &str) -> String {
+        export(day, &example(day, name), Format::Json).unwrap()
+    }
+
+    /// the parsed inputs borrow from the input text, the deserialized ones from `json` where they
+    /// can.
+    fn assert_json_round_trip<'a, T>(json: &'a str, parsed: T)
+    where
+        T: Deserialize<'a> + Debug + PartialEq,
+    {
+        assert_eq!(parsed, serde_json::from_str::<T>(json).unwrap());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let input = |day| example(day, "example");
+
+        assert_json_round_trip(
+            &json(2, "example"),
+            day2::input_generator(&input(2)).unwrap(),
+        );
+        assert_json_round_trip(
+            &json(3, "example"),
+            day3::input_generator(&input(3)).unwrap(),
+        );
+        assert_json_round_trip(
+            &json(4, "example"),
+            day4::input_generator(&input(4)).unwrap(),
+        );
+        assert_json_round_trip(
+            &json(5, "example"),
+            day5::input_generator(&input(5)).unwrap(),
+        );
+        assert_json_round_trip(
+            &json(7, "example1"),
+            day7::input_generator(&example(7, "example1")).unwrap(),
+        );
+        assert_json_round_trip(
+            &json(8, "example"),
+            day8::halt_on_loop(&day8::input_generator(&input(8)).unwrap()),
+        );
+        assert_json_round_trip(
+            &json(11, "example"),
+            day11::Ferry::new(day11::input_generator(&input(11)).unwrap()),
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip_with_escapes() {
+        let passports = r#"ecl:"gry" pid:\860033327"#;
+        let rules = "a \"b\" bags contain 2 c\\d e bags.\nc\\d e bags contain no other bags.";
+
+        assert_json_round_trip(
+            &export(4, passports, Format::Json).unwrap(),
+            day4::input_generator(passports).unwrap(),
+        );
+        assert_json_round_trip(
+            &export(7, rules, Format::Json).unwrap(),
+            day7::input_generator(rules).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_json_contains_the_vm_state() {
+        let json = export(8, "nop +0\nacc +3\njmp -1", Format::Json).unwrap();
+        let vm: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(1, vm["ptr"]);
+        assert_eq!(3, vm["state"]);
+    }
+
+    #[test]
+    fn test_cyclic_rules_are_not_deserialized() {
+        let error = serde_json::from_str::<day7::Rules>(r#"{"a b": {"a b": 1}}"#).unwrap_err();
+
+        assert!(error.to_string().contains("`a b` bags contain themselves"));
+    }
+
+    #[test]
+    fn test_json_is_deterministic() {
+        let input = example(4, "example");
+
+        assert_eq!(
+            export(4, &input, Format::Json).unwrap(),
+            export(4, &input, Format::Json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            "row,seat,id\n44,5,357\n70,7,567\n14,7,119\n102,4,820\n",
+            export(5, &example(5, "example"), Format::Csv).unwrap()
+        );
+        assert_eq!(
+            "color,content,count\na b,c d,2\na b,e f,1\nc d,,0\n",
+            export(
+                7,
+                "a b bags contain 1 e f bag, 2 c d bags.\nc d bags contain no other bags.",
+                Format::Csv
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "group_size,answers\n2,a:2 b:1\n",
+            export(6, "ab\na", Format::Csv).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_every_day_can_be_exported() {
+        for day in 1..=11 {
+            let input = crate::synth::generate(day, 0, 20).unwrap().input;
+
+            for format in [Format::Json, Format::Csv] {
+                assert!(export(day, &input, format).is_ok(), "day {}", day);
+            }
+        }
+
+        assert!(matches!(
+            export(12, "", Format::Json),
+            Err(ExportError::UnknownDay(12))
+        ));
+    }
+}
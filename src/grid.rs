@@ -0,0 +1,334 @@
+//! Dense two dimensional grids for the puzzles whose input is a map of characters.
+//!
+//! The cells are stored row by row in a single `Vec`. Every axis either ends at the border of the
+//! grid or wraps around to the other side, e.g. the map of day 3 repeats to the right.
+
+use crate::parse::{self, Invalid, ParseError};
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::iter;
+use std::mem;
+use std::ops::{Index, IndexMut};
+
+/// position of a cell as `(x, y)`, i.e. column and row.
+pub type Position = (usize, usize);
+
+/// direction and distance of a step as `(x, y)`.
+pub type Vector = (isize, isize);
+
+/// directions of the neighbours sharing an edge with a cell.
+pub const DIRECTIONS4: [Vector; 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// directions of the neighbours sharing an edge or a corner with a cell.
+pub const DIRECTIONS8: [Vector; 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// What happens beyond the border of an axis.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Edge {
+    /// there are no cells beyond the border
+    Bounded,
+    /// the axis repeats, leaving at one side enters at the other
+    Wrapping,
+}
+
+impl Edge {
+    /// the coordinate of `value` on an axis of `size` cells.
+    fn resolve(self, value: isize, size: usize) -> Option<usize> {
+        match self {
+            _ if size == 0 => None,
+            Edge::Bounded => usize::try_from(value).ok().filter(|value| *value < size),
+            Edge::Wrapping => Some(value.rem_euclid(size as isize) as usize),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+    horizontal: Edge,
+    vertical: Edge,
+}
+
+impl<T> Grid<T> {
+    /// grid of the given width holding `cells` row by row, bounded on both axes.
+    ///
+    /// # Panics
+    ///
+    /// if the number of cells isn't a multiple of `width`.
+    pub fn new(width: usize, cells: Vec<T>) -> Self {
+        let height = match width {
+            0 => 0,
+            width => cells.len() / width,
+        };
+        assert_eq!(width * height, cells.len(), "the rows have to be complete");
+
+        Self {
+            width,
+            height,
+            cells,
+            horizontal: Edge::Bounded,
+            vertical: Edge::Bounded,
+        }
+    }
+
+    /// the same grid with the given edges.
+    pub fn with_edges(self, horizontal: Edge, vertical: Edge) -> Self {
+        Self {
+            horizontal,
+            vertical,
+            ..self
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// the cells row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// exchanges the cells with `cells`, e.g. to switch to the next generation of an automaton.
+    ///
+    /// # Panics
+    ///
+    /// if the number of cells differs.
+    pub fn swap_cells(&mut self, cells: &mut Vec<T>) {
+        assert_eq!(
+            self.cells.len(),
+            cells.len(),
+            "the grid can't change its size"
+        );
+        mem::swap(&mut self.cells, cells);
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` doesn't accept a size of 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// every cell and its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        let width = self.width;
+
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(index, cell)| ((index % width, index / width), cell))
+    }
+
+    /// index of `position` in [`cells`](Grid::cells), after wrapping it around wrapping axes.
+    pub fn cell_index(&self, (x, y): Position) -> Option<usize> {
+        let x = self.horizontal.resolve(x as isize, self.width)?;
+        let y = self.vertical.resolve(y as isize, self.height)?;
+
+        Some(y * self.width + x)
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.cell_index(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.cell_index(position)
+            .map(move |index| &mut self.cells[index])
+    }
+
+    /// the position one `step` away from `position`, if there is a cell.
+    pub fn step(&self, (x, y): Position, (dx, dy): Vector) -> Option<Position> {
+        Some((
+            self.horizontal.resolve(x as isize + dx, self.width)?,
+            self.vertical.resolve(y as isize + dy, self.height)?,
+        ))
+    }
+
+    /// the cells one step away from `position` in each of `directions`.
+    pub fn neighbours<'a>(
+        &'a self,
+        position: Position,
+        directions: &'a [Vector],
+    ) -> impl Iterator<Item = (Position, &'a T)> + 'a {
+        directions
+            .iter()
+            .filter_map(move |direction| self.step(position, *direction))
+            .map(move |position| (position, &self[position]))
+    }
+
+    /// the up to 4 cells sharing an edge with `position`.
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbours(position, &DIRECTIONS4)
+    }
+
+    /// the up to 8 cells sharing an edge or a corner with `position`.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbours(position, &DIRECTIONS8)
+    }
+
+    /// the cells reached by repeatedly stepping by `step` from `position`, excluding `position`
+    /// itself. Ends at a bounded edge, so it is endless if `step` only moves along wrapping axes.
+    pub fn ray(&self, position: Position, step: Vector) -> impl Iterator<Item = (Position, &T)> {
+        iter::successors(self.step(position, step), move |position| {
+            self.step(*position, step)
+        })
+        .map(move |position| (position, &self[position]))
+    }
+
+    /// grid of the same size and edges with `f` applied to the position and value of every cell.
+    pub fn map<U>(&self, mut f: impl FnMut(Position, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self
+                .iter()
+                .map(|(position, cell)| f(position, cell))
+                .collect(),
+            horizontal: self.horizontal,
+            vertical: self.vertical,
+        }
+    }
+
+    /// parses a grid with a character per cell, turning every character into a cell using `cell`.
+    /// All rows have to be of the same width.
+    pub fn parse<F>(day: u32, input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, Invalid>,
+    {
+        let mut width = None;
+        let mut cells = Vec::with_capacity(input.len());
+
+        parse::lines(day, input, |line| {
+            let mut columns = 0;
+            for (column, c) in line.chars().enumerate() {
+                cells.push(cell(c).map_err(|e| e.offset(0, column))?);
+                columns += 1;
+            }
+
+            match *width.get_or_insert(columns) {
+                _ if columns == 0 => Err(Invalid::new(0, "empty row")),
+                width if width != columns => Err(Invalid::new(
+                    columns.min(width),
+                    format!("expected a row of {} cells, found {}", width, columns),
+                )),
+                _ => Ok(()),
+            }
+        })?;
+
+        Ok(Grid::new(width.unwrap_or(0), cells))
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", position))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", position))
+    }
+}
+
+/// writes a row per line, using the display of the cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::*;
+
+    fn digit(c: char) -> Result<u32, Invalid> {
+        c.to_digit(10)
+            .ok_or_else(|| Invalid::new(0, "expected a digit"))
+    }
+
+    fn example() -> Grid<u32> {
+        Grid::parse(0, "123\n456\n789\n", digit).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = example();
+
+        assert_eq!((3, 3), (grid.width(), grid.height()));
+        assert_eq!(6, grid[(2, 1)]);
+        assert_eq!("123\n456\n789\n", grid.to_string());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Grid::parse(0, "12\n3x", digit).unwrap_err();
+        assert_eq!(
+            (2, 2, "expected a digit"),
+            (error.line(), error.column(), error.message())
+        );
+
+        let error = Grid::parse(0, "12\n345", digit).unwrap_err();
+        assert_eq!(
+            (2, 3, "expected a row of 2 cells, found 3"),
+            (error.line(), error.column(), error.message())
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = example();
+        let values = |neighbours: Vec<(Position, &u32)>| -> Vec<u32> {
+            neighbours.into_iter().map(|(_, value)| *value).collect()
+        };
+
+        assert_eq!(vec![2, 4], values(grid.neighbours4((0, 0)).collect()));
+        assert_eq!(vec![2, 4, 5], values(grid.neighbours8((0, 0)).collect()));
+        assert_eq!(8, grid.neighbours8((1, 1)).count());
+
+        let grid = grid.with_edges(Edge::Wrapping, Edge::Wrapping);
+        assert_eq!(vec![7, 3, 2, 4], values(grid.neighbours4((0, 0)).collect()));
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = example();
+        let ray: Vec<Position> = grid.ray((0, 0), (1, 1)).map(|(p, _)| p).collect();
+        assert_eq!(vec![(1, 1), (2, 2)], ray);
+
+        let grid = grid.with_edges(Edge::Wrapping, Edge::Bounded);
+        let ray: Vec<u32> = grid.ray((0, 0), (2, 1)).map(|(_, v)| *v).collect();
+        assert_eq!(vec![6, 8], ray);
+        assert_eq!(Some(&1), grid.get((6, 0)));
+        assert_eq!(None, grid.get((0, 3)));
+    }
+}
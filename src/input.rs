@@ -0,0 +1,187 @@
+//! Downloads the puzzle inputs and caches them next to the other inputs.
+//!
+//! The [`Client`] also submits answers, see [`submit`](crate::submit) for the rules around that.
+//!
+//! A cached input is never downloaded again, so the solutions keep working offline once every
+//! input has been fetched. The base URL is configurable, which allows testing against a local
+//! server instead of adventofcode.com.
+
+use crate::runner::YEAR;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// environment variable holding the value of the `session` cookie
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// asked for by the maintainers of advent of code, so they can reach out if the tool misbehaves
+const USER_AGENT: &str = "github.com/j-brn/advent_of_code_2020";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Error talking to the advent of code website.
+#[derive(Debug)]
+pub enum FetchError {
+    /// there is no session token to authenticate the request
+    NoSession,
+    /// the server rejected the session token, it is most likely expired
+    InvalidSession,
+    /// the puzzle doesn't exist or isn't unlocked yet
+    NotUnlocked {
+        day: u32,
+    },
+    Status {
+        code: u16,
+        message: String,
+    },
+    Network(String),
+    Io(PathBuf, io::Error),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::NoSession => write!(
+                f,
+                "no session token, pass --session or set {} to the value of the session cookie",
+                SESSION_VAR
+            ),
+            FetchError::InvalidSession => write!(
+                f,
+                "the session token was rejected, it has probably expired. log in again and copy \
+                 the new session cookie"
+            ),
+            FetchError::NotUnlocked { day } => {
+                write!(f, "day {} doesn't exist or isn't unlocked yet", day)
+            }
+            FetchError::Status { code, message } => {
+                write!(f, "unexpected response {}: {}", code, message)
+            }
+            FetchError::Network(message) => write!(f, "request failed: {}", message),
+            FetchError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+/// Client for the advent of code website.
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    /// client for the server at `base_url`, authenticated by the value of the session cookie.
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(TIMEOUT)
+            .build();
+
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            agent,
+        }
+    }
+
+    /// downloads the puzzle input of `day`.
+    pub fn download(&self, day: u32) -> Result<String, FetchError> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, YEAR, day);
+        let request = self.authenticate(self.agent.get(&url))?;
+
+        read(day, request.call())
+    }
+
+    /// posts `answer` for `part` of `day` and returns the page of the response.
+    pub fn submit(&self, day: u32, part: u32, answer: &str) -> Result<String, FetchError> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, YEAR, day);
+        let request = self.authenticate(self.agent.post(&url))?;
+        let level = part.to_string();
+
+        read(
+            day,
+            request.send_form(&[("level", &level), ("answer", answer)]),
+        )
+    }
+
+    fn authenticate(&self, request: ureq::Request) -> Result<ureq::Request, FetchError> {
+        let session = self.session.as_ref().ok_or(FetchError::NoSession)?;
+
+        Ok(request.set("Cookie", &format!("session={}", session)))
+    }
+}
+
+/// reads the body of the response to a request concerning `day`.
+fn read(day: u32, response: Result<ureq::Response, ureq::Error>) -> Result<String, FetchError> {
+    let response = response.map_err(|e| match e {
+        ureq::Error::Status(400, _) | ureq::Error::Status(401, _) | ureq::Error::Status(403, _) => {
+            FetchError::InvalidSession
+        }
+        ureq::Error::Status(404, _) => FetchError::NotUnlocked { day },
+        ureq::Error::Status(code, response) => FetchError::Status {
+            code,
+            message: response
+                .into_string()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        },
+        ureq::Error::Transport(e) => FetchError::Network(e.to_string()),
+    })?;
+
+    response
+        .into_string()
+        .map_err(|e| FetchError::Network(e.to_string()))
+}
+
+/// Puzzle inputs stored in a directory as `day<day>.txt`, the layout used by cargo-aoc.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.dir.join(format!("day{}.txt", day))
+    }
+
+    pub fn contains(&self, day: u32) -> bool {
+        self.path(day).is_file()
+    }
+
+    /// the cached input of `day`, downloading and caching it using `client` if it isn't cached.
+    pub fn get(&self, client: &Client, day: u32) -> Result<String, FetchError> {
+        let path = self.path(day);
+        if self.contains(day) {
+            return fs::read_to_string(&path).map_err(|e| FetchError::Io(path, e));
+        }
+
+        let input = client.download(day)?;
+        write_atomically(&path, &input).map_err(|e| FetchError::Io(path, e))?;
+
+        Ok(input)
+    }
+}
+
+/// writes `content` to a temporary file first, so an interrupted download can't leave a truncated
+/// input in the cache.
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temporary = path.with_extension("txt.part");
+    fs::write(&temporary, content)?;
+    fs::rename(&temporary, path)
+}
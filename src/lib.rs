@@ -2,6 +2,15 @@
 extern crate lazy_static;
 use aoc_runner_derive::aoc_lib;
 
+pub mod answer;
+pub mod answers;
+pub mod automaton;
+pub mod batch;
+pub mod cancel;
+// declared before the days, which use its macro in their tests
+#[cfg(test)]
+#[macro_use]
+mod examples;
 mod day1;
 mod day10;
 mod day11;
@@ -13,5 +22,25 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+#[cfg(feature = "serde")]
+pub mod export;
+pub mod grid;
+pub mod input;
+pub mod params;
+pub mod parse;
+pub mod pool;
+pub mod profile;
+pub mod registry;
+pub mod render;
+pub mod runner;
+pub mod solution;
+pub mod submit;
+pub mod summary;
+pub mod synth;
+
+pub use answer::Answer;
+pub use parse::ParseError;
+pub use registry::Registry;
+pub use solution::{Runner, Solution};
 
 aoc_lib! { year = 2020 }
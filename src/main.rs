@@ -1,3 +1,862 @@
-use aoc_runner_derive::aoc_main;
+use advent_of_code_2020::answers::{self, Answers, Key, Verdict};
+use advent_of_code_2020::batch;
+use advent_of_code_2020::input::{self, Cache, Client, FetchError};
+use advent_of_code_2020::params::{Listing, Overrides};
+use advent_of_code_2020::pool;
+use advent_of_code_2020::profile::{self, Table};
+use advent_of_code_2020::render::{self, RenderError};
+use advent_of_code_2020::solution::{self, Outcome};
+use advent_of_code_2020::submit::{self, Attempt, Feedback, History, Reply, Submission};
+use advent_of_code_2020::summary::{Status, Summary};
+use advent_of_code_2020::{runner, synth};
+use advent_of_code_2020::{Answer, ParseError, Registry, Runner};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::{self, Read};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use structopt::StructOpt;
 
-aoc_main! { lib = advent_of_code_2020 }
+/// exit code for inputs that can't be read or parsed and for unknown puzzles
+const EXIT_FAILURE: i32 = 1;
+/// exit code for answers that don't match the expected value
+const EXIT_MISMATCH: i32 = 2;
+
+#[derive(StructOpt)]
+#[structopt(about = "my solutions for adventofcode 2020")]
+struct Options {
+    /// override a parameter of a solution, e.g. `9.preamble=5`
+    #[structopt(long = "param", global = true, number_of_values = 1)]
+    params: Vec<String>,
+    /// file of parameter overrides with a `<day>.<key> = <value>` per line, overridden by `--param`
+    #[structopt(long, global = true)]
+    config: Option<PathBuf>,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Solve a single part of a puzzle
+    Run {
+        #[structopt(flatten)]
+        puzzle: PuzzleArgs,
+    },
+    /// Solve every part of every puzzle concurrently using the inputs in `input/2020` and compare
+    /// the answers with the known ones
+    RunAll {
+        /// number of puzzles to solve at the same time [default: number of cpus]
+        #[structopt(long)]
+        threads: Option<usize>,
+        /// file containing the known answers
+        #[structopt(long, default_value = answers::DEFAULT_PATH)]
+        answers: PathBuf,
+        /// time limit per part in seconds, `0` disables it
+        #[structopt(long, default_value = "60")]
+        timeout: Limit,
+    },
+    /// Solve the puzzles and compare the answers with the known ones
+    Verify {
+        /// verify only this day
+        #[structopt(long)]
+        day: Option<u32>,
+        /// verify only this part
+        #[structopt(long)]
+        part: Option<u32>,
+        /// path of the puzzle input or `-` to read it from stdin [default: input/2020/day<day>.txt]
+        #[structopt(long, requires = "day")]
+        input: Option<PathBuf>,
+        /// compare with this answer instead of the known one
+        #[structopt(long, requires_all = &["day", "part"])]
+        expect: Option<Answer>,
+        /// file containing the known answers
+        #[structopt(long, default_value = answers::DEFAULT_PATH)]
+        answers: PathBuf,
+        /// add the answers of inputs without a known answer to the answers file
+        #[structopt(long)]
+        record: bool,
+    },
+    /// Solve the puzzles repeatedly and report the time spent parsing and solving
+    Bench {
+        /// benchmark only this day
+        #[structopt(long)]
+        day: Option<u32>,
+        /// benchmark only this part
+        #[structopt(long)]
+        part: Option<u32>,
+        /// path of the puzzle input or `-` to read it from stdin [default: input/2020/day<day>.txt]
+        #[structopt(long, requires = "day")]
+        input: Option<PathBuf>,
+        #[structopt(long, default_value = "100")]
+        iterations: u32,
+        /// `table` or `json`
+        #[structopt(long, default_value = "table")]
+        format: Format,
+    },
+    /// Solve the puzzles for every input in `<dir>/day<day>/*.txt`, e.g. the inputs of several
+    /// accounts
+    Batch {
+        /// directory containing a directory of inputs per day [default: input/2020]
+        #[structopt(long)]
+        dir: Option<PathBuf>,
+        /// run only this day
+        #[structopt(long)]
+        day: Option<u32>,
+        /// time limit per part in seconds, `0` disables it
+        #[structopt(long, default_value = "60")]
+        timeout: Limit,
+    },
+    /// Download the puzzle inputs which aren't cached yet
+    Fetch {
+        /// download only this day [default: every day with a solution]
+        #[structopt(long)]
+        day: Option<u32>,
+        /// value of the session cookie of adventofcode.com
+        #[structopt(long, env = input::SESSION_VAR, hide_env_values = true)]
+        session: Option<String>,
+        #[structopt(long, env = "AOC_BASE_URL", default_value = input::DEFAULT_BASE_URL)]
+        base_url: String,
+        /// directory the inputs are cached in [default: input/2020]
+        #[structopt(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Solve a part and submit the answer, unless it is known to be wrong or the server asked to
+    /// wait
+    Submit {
+        #[structopt(flatten)]
+        puzzle: PuzzleArgs,
+        /// value of the session cookie of adventofcode.com
+        #[structopt(long, env = input::SESSION_VAR, hide_env_values = true)]
+        session: Option<String>,
+        #[structopt(long, env = "AOC_BASE_URL", default_value = input::DEFAULT_BASE_URL)]
+        base_url: String,
+        /// file recording every submission
+        #[structopt(long, default_value = submit::DEFAULT_PATH)]
+        history: PathBuf,
+    },
+    /// Print the parsed input as json or csv, requires the `serde` feature
+    Export {
+        #[structopt(long)]
+        day: u32,
+        /// path of the puzzle input or `-` to read it from stdin [default: input/2020/day<day>.txt]
+        #[structopt(long)]
+        input: Option<PathBuf>,
+        /// `json` or `csv`
+        #[structopt(long, default_value = "json")]
+        format: String,
+    },
+    /// Render the paths of day 3 or every generation of the seats of day 11 as images, png and
+    /// gif require the `images` feature
+    Render {
+        #[structopt(flatten)]
+        puzzle: PuzzleArgs,
+        /// directory of the frames, or the file of a gif
+        #[structopt(long)]
+        output: PathBuf,
+        /// `ppm`, `png` or `gif`
+        #[structopt(long, default_value = "ppm")]
+        format: String,
+        /// width and height of a cell in pixels
+        #[structopt(long, default_value = "4")]
+        scale: usize,
+        /// comma separated `#rrggbb` colors replacing the colors of the day
+        #[structopt(long)]
+        palette: Option<String>,
+        /// milliseconds between the frames of a gif
+        #[structopt(long, default_value = "100")]
+        delay: u32,
+    },
+    /// Print the parameters of every solution, with the overrides applied, in the format of
+    /// `--config`
+    Params {
+        /// print only the parameters of this day
+        #[structopt(long)]
+        day: Option<u32>,
+    },
+    /// Print a synthetic input to stdout and its answers to stderr
+    Generate {
+        #[structopt(long)]
+        day: u32,
+        #[structopt(long, default_value = "0")]
+        seed: u64,
+        /// number of lines, passports, groups, ... of the input
+        #[structopt(long, default_value = "100")]
+        size: usize,
+    },
+}
+
+enum Format {
+    Table,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown format `{}`, expected `table` or `json`",
+                s
+            )),
+        }
+    }
+}
+
+/// Time limit of a single run.
+struct Limit(Option<Duration>);
+
+impl FromStr for Limit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<f64>() {
+            Ok(0.0) => Ok(Limit(None)),
+            Ok(seconds) if seconds > 0.0 && seconds.is_finite() => {
+                Ok(Limit(Some(Duration::from_secs_f64(seconds))))
+            }
+            _ => Err(format!("invalid time limit `{}`, expected seconds", s)),
+        }
+    }
+}
+
+#[derive(StructOpt)]
+struct PuzzleArgs {
+    #[structopt(long)]
+    day: u32,
+    #[structopt(long)]
+    part: u32,
+    /// path of the puzzle input or `-` to read it from stdin [default: input/2020/day<day>.txt]
+    #[structopt(long)]
+    input: Option<PathBuf>,
+}
+
+/// reads the input from `path`, stdin if `path` is `-` or the default location of the input of
+/// `day` if there is no path.
+fn read_input(day: u32, path: Option<&Path>) -> Result<String, Error> {
+    let path = match path {
+        Some(path) if path == Path::new("-") => {
+            let mut input = String::new();
+            return io::stdin()
+                .read_to_string(&mut input)
+                .map(|_| input)
+                .map_err(Error::io("read", "stdin"));
+        }
+        Some(path) => path.to_path_buf(),
+        None => PathBuf::from(runner::default_input_path(day)),
+    };
+
+    fs::read_to_string(&path).map_err(Error::io("read", path.display()))
+}
+
+enum Error {
+    Io {
+        action: &'static str,
+        path: String,
+        source: io::Error,
+    },
+    Parse(ParseError),
+    Fetch(u32, FetchError),
+    Params(String),
+    Refused(submit::Refusal),
+    Export(String),
+    Render(String),
+    Rejected(Feedback),
+    UnknownPuzzle(u32, u32),
+    NoGenerator(u32),
+    Mismatch {
+        expected: Answer,
+        actual: Answer,
+    },
+    WrongAnswers(usize),
+    Failures(usize),
+}
+
+impl Error {
+    /// maps the error of `action` (e.g. `read`) on `path`.
+    fn io(action: &'static str, path: impl Display) -> impl FnOnce(io::Error) -> Error {
+        let path = path.to_string();
+        move |source| Error::Io {
+            action,
+            path,
+            source,
+        }
+    }
+
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::Mismatch { .. } | Error::WrongAnswers(_) => EXIT_MISMATCH,
+            Error::Rejected(feedback) if feedback.is_wrong() => EXIT_MISMATCH,
+            _ => EXIT_FAILURE,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                action,
+                path,
+                source,
+            } => write!(f, "failed to {} {}: {}", action, path, source),
+            Error::Parse(e) => write!(f, "failed to parse the input:\n{}", e),
+            Error::Fetch(day, e) => write!(f, "failed to fetch the input of day {}: {}", day, e),
+            Error::Params(message) => write!(f, "invalid parameters: {}", message),
+            Error::Refused(refusal) => write!(f, "not submitted: {}", refusal),
+            Error::Export(message) => write!(f, "{}", message),
+            Error::Render(message) => write!(f, "{}", message),
+            Error::Rejected(feedback) => write!(f, "the answer was not accepted: {}", feedback),
+            Error::UnknownPuzzle(day, part) => {
+                write!(f, "no solution for day {} part {}", day, part)
+            }
+            Error::NoGenerator(day) => write!(f, "no input generator for day {}", day),
+            Error::Mismatch { expected, actual } => {
+                write!(
+                    f,
+                    "wrong answer: expected {}, got {}",
+                    expected.to_line(),
+                    actual.to_line()
+                )
+            }
+            Error::WrongAnswers(count) => write!(f, "{} wrong answers", count),
+            Error::Failures(count) => write!(f, "{} runs failed", count),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+fn find(registry: &Registry, day: u32, part: u32) -> Result<&dyn Runner, Error> {
+    registry
+        .get(day, part)
+        .ok_or(Error::UnknownPuzzle(day, part))
+}
+
+/// runners of the given day and part, or all of them if they aren't given.
+fn select(
+    registry: &Registry,
+    day: Option<u32>,
+    part: Option<u32>,
+) -> Result<Vec<&dyn Runner>, Error> {
+    let runners = registry
+        .runners()
+        .filter(|runner| day.is_none_or(|day| runner.day() == day))
+        .filter(|runner| part.is_none_or(|part| runner.part() == part))
+        .collect::<Vec<_>>();
+
+    if runners.is_empty() {
+        return Err(Error::UnknownPuzzle(day.unwrap_or(0), part.unwrap_or(0)));
+    }
+
+    Ok(runners)
+}
+
+fn run(registry: &Registry, puzzle: PuzzleArgs) -> Result<(), Error> {
+    let runner = find(registry, puzzle.day, puzzle.part)?;
+    let input = read_input(puzzle.day, puzzle.input.as_deref())?;
+    let answer = runner.solve(&input)?;
+
+    println!("{}", answer);
+    Ok(())
+}
+
+fn load_answers(path: &Path) -> Result<Answers, Error> {
+    match Answers::load(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+        known => known.map_err(Error::io("read", path.display())),
+    }
+}
+
+fn run_all(
+    registry: &Registry,
+    threads: Option<usize>,
+    path: PathBuf,
+    limit: Limit,
+) -> Result<(), Error> {
+    let known = load_answers(&path)?;
+    let runners: Vec<&dyn Runner> = registry.runners().collect();
+    let inputs: BTreeMap<u32, Result<String, String>> = runners
+        .iter()
+        .map(|runner| {
+            let input = read_input(runner.day(), None).map_err(|e| e.to_string());
+            (runner.day(), input)
+        })
+        .collect();
+
+    // panics are reported with the results instead of in between
+    panic::set_hook(Box::new(|_| {}));
+
+    let results = pool::map(
+        &runners,
+        threads.unwrap_or_else(pool::default_threads),
+        |runner| match &inputs[&runner.day()] {
+            Ok(input) => {
+                let outcome = solution::run_isolated(*runner, input, limit.0);
+                let status = Status::of(&outcome, &known, &Key::of(*runner, input));
+                (outcome, status)
+            }
+            Err(e) => (Outcome::Failed(e.clone()), Status::Failed),
+        },
+    );
+
+    for (runner, (outcome, status)) in runners.iter().zip(&results) {
+        let details = match outcome {
+            Outcome::Solved(run) => format!(
+                "{:<20} ({:.2?})",
+                run.answer.to_line(),
+                run.parse_time + run.solve_time
+            ),
+            Outcome::Failed(e) | Outcome::Panicked(e) => e.clone(),
+            Outcome::TimedOut(limit) => format!("cancelled after {:?}", limit),
+        };
+
+        println!(
+            "day {:>2} part {}: {:<9}  {}",
+            runner.day(),
+            runner.part(),
+            status,
+            details
+        );
+    }
+
+    let statuses: Vec<(u32, u32, Status)> = runners
+        .iter()
+        .zip(&results)
+        .map(|(runner, (_, status))| (runner.day(), runner.part(), *status))
+        .collect();
+    println!("\n{}", Summary(&statuses));
+
+    let count = |wanted: &[Status]| {
+        statuses
+            .iter()
+            .filter(|(_, _, status)| wanted.contains(status))
+            .count()
+    };
+
+    match (
+        count(&[Status::Failed, Status::Panicked, Status::TimedOut]),
+        count(&[Status::Wrong]),
+    ) {
+        (0, 0) => Ok(()),
+        (0, wrong) => Err(Error::WrongAnswers(wrong)),
+        (failures, _) => Err(Error::Failures(failures)),
+    }
+}
+
+fn verify_expected(registry: &Registry, puzzle: PuzzleArgs, expected: Answer) -> Result<(), Error> {
+    let runner = find(registry, puzzle.day, puzzle.part)?;
+    let input = read_input(puzzle.day, puzzle.input.as_deref())?;
+    let actual = runner.solve(&input)?;
+
+    if actual != expected {
+        return Err(Error::Mismatch { expected, actual });
+    }
+
+    println!("{}", actual);
+    Ok(())
+}
+
+fn verify(
+    registry: &Registry,
+    day: Option<u32>,
+    part: Option<u32>,
+    input: Option<PathBuf>,
+    path: PathBuf,
+    record: bool,
+) -> Result<(), Error> {
+    let mut known = load_answers(&path)?;
+    let runners = select(registry, day, part)?;
+    let (mut wrong, mut unknown, mut failed) = (0, Vec::new(), None);
+
+    for runner in &runners {
+        let verification = match read_input(runner.day(), input.as_deref()) {
+            Ok(input) => answers::verify(&known, *runner, &input),
+            Err(e) => {
+                eprintln!("day {:>2} part {}: {}", runner.day(), runner.part(), e);
+                failed = Some(e);
+                continue;
+            }
+        };
+        println!("{}", verification);
+
+        match verification.result {
+            Ok((_, Verdict::Wrong { .. })) => wrong += 1,
+            Ok((answer, Verdict::Unknown)) => unknown.push((verification.key, answer)),
+            Err(e) => failed = Some(e.into()),
+            Ok((_, Verdict::Correct)) => {}
+        }
+    }
+
+    println!(
+        "{} puzzles, {} wrong, {} unknown",
+        runners.len(),
+        wrong,
+        unknown.len()
+    );
+
+    if record && !unknown.is_empty() {
+        for (key, answer) in unknown {
+            known.insert(key, answer);
+        }
+        known
+            .save(&path)
+            .map_err(Error::io("write", path.display()))?;
+    }
+
+    match failed {
+        Some(e) => Err(e),
+        None if wrong > 0 => Err(Error::WrongAnswers(wrong)),
+        None => Ok(()),
+    }
+}
+
+fn bench(
+    registry: &Registry,
+    day: Option<u32>,
+    part: Option<u32>,
+    input: Option<PathBuf>,
+    iterations: u32,
+    format: Format,
+) -> Result<(), Error> {
+    let runners = select(registry, day, part)?;
+    let mut profiles = Vec::with_capacity(runners.len());
+
+    for runner in runners {
+        let input = read_input(runner.day(), input.as_deref())?;
+        profiles.push(profile::profile(runner, &input, iterations)?);
+    }
+
+    match format {
+        Format::Table => print!("{}", Table(&profiles)),
+        Format::Json => println!("{}", profile::to_json(&profiles)),
+    }
+
+    Ok(())
+}
+
+fn run_batch(
+    registry: &Registry,
+    dir: Option<PathBuf>,
+    day: Option<u32>,
+    limit: Limit,
+) -> Result<(), Error> {
+    let dir = dir.unwrap_or_else(|| PathBuf::from(runner::default_input_dir()));
+    let days: Vec<u32> = match day {
+        Some(day) => vec![find(registry, day, 1)?.day()],
+        None => {
+            let mut days: Vec<u32> = registry.runners().map(|runner| runner.day()).collect();
+            days.dedup();
+            days.retain(|day| dir.join(format!("day{}", day)).is_dir());
+            days
+        }
+    };
+    let mut failures = 0;
+
+    // panics are reported below the matrix instead of in between
+    panic::set_hook(Box::new(|_| {}));
+
+    for day in days {
+        let paths = batch::input_paths(&dir, day)
+            .map_err(Error::io("list", dir.join(format!("day{}", day)).display()))?;
+        let runners: Vec<&dyn Runner> = registry.day(day).collect();
+        let matrix = batch::run(day, &runners, &paths, limit.0);
+
+        println!("{}", matrix);
+        for (path, outcomes) in &matrix.rows {
+            for (part, outcome) in matrix.parts.iter().zip(outcomes) {
+                match outcome {
+                    Outcome::Failed(e) => eprintln!("{} part {}: {}", path.display(), part, e),
+                    Outcome::Panicked(e) => {
+                        eprintln!("{} part {}: panicked: {}", path.display(), part, e)
+                    }
+                    Outcome::TimedOut(limit) => eprintln!(
+                        "{} part {}: cancelled after {:?}",
+                        path.display(),
+                        part,
+                        limit
+                    ),
+                    Outcome::Solved(_) => {}
+                }
+            }
+        }
+        failures += matrix.failures();
+    }
+
+    match failures {
+        0 => Ok(()),
+        failures => Err(Error::Failures(failures)),
+    }
+}
+
+fn fetch(
+    registry: &Registry,
+    day: Option<u32>,
+    session: Option<String>,
+    base_url: &str,
+    dir: Option<PathBuf>,
+) -> Result<(), Error> {
+    let days: Vec<u32> = match day {
+        Some(day) => vec![day],
+        None => {
+            let mut days: Vec<u32> = registry.runners().map(|runner| runner.day()).collect();
+            days.dedup();
+            days
+        }
+    };
+    let cache = Cache::new(dir.unwrap_or_else(|| PathBuf::from(runner::default_input_dir())));
+    let client = Client::new(base_url, session);
+
+    for day in days {
+        if cache.contains(day) {
+            println!("day {:>2}: cached", day);
+            continue;
+        }
+
+        cache.get(&client, day).map_err(|e| Error::Fetch(day, e))?;
+        println!(
+            "day {:>2}: downloaded to {}",
+            day,
+            cache.path(day).display()
+        );
+    }
+
+    Ok(())
+}
+
+fn submit(
+    registry: &Registry,
+    puzzle: PuzzleArgs,
+    session: Option<String>,
+    base_url: &str,
+    path: PathBuf,
+) -> Result<(), Error> {
+    let runner = find(registry, puzzle.day, puzzle.part)?;
+    let input = read_input(puzzle.day, puzzle.input.as_deref())?;
+    let answer = runner.solve(&input)?;
+
+    let mut history = match History::load(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => History::default(),
+        history => history.map_err(Error::io("read", path.display()))?,
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    history
+        .check(puzzle.day, puzzle.part, &answer, now)
+        .map_err(Error::Refused)?;
+
+    let page = Client::new(base_url, session)
+        .submit(puzzle.day, puzzle.part, &answer.to_string())
+        .map_err(|e| Error::Fetch(puzzle.day, e))?;
+    let reply = Reply::parse(&page);
+    let attempt = Attempt {
+        time: now,
+        year: runner::YEAR,
+        day: puzzle.day,
+        part: puzzle.part,
+        feedback: reply.feedback,
+        wait: reply.wait.map_or(0, |wait| wait.as_secs()),
+        answer,
+    };
+
+    println!("{}", Submission(&attempt));
+    // the server didn't look at the answer, so there is nothing to learn from it
+    if reply.feedback != Feedback::Solved {
+        history.record(attempt);
+        history
+            .save(&path)
+            .map_err(Error::io("write", path.display()))?;
+    }
+
+    match reply.feedback {
+        Feedback::Correct => Ok(()),
+        Feedback::Unrecognized => {
+            eprintln!("{}", reply.message);
+            Err(Error::Rejected(reply.feedback))
+        }
+        feedback => Err(Error::Rejected(feedback)),
+    }
+}
+
+#[cfg(feature = "serde")]
+fn export(day: u32, input: Option<PathBuf>, format: String) -> Result<(), Error> {
+    use advent_of_code_2020::export::{self, ExportError, Format};
+
+    let format: Format = format.parse().map_err(Error::Export)?;
+    let input = read_input(day, input.as_deref())?;
+    let exported = export::export(day, &input, format).map_err(|e| match e {
+        ExportError::Parse(e) => Error::Parse(e),
+        e => Error::Export(e.to_string()),
+    })?;
+
+    print!("{}", exported);
+    if format == Format::Json {
+        println!();
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "serde"))]
+fn export(_: u32, _: Option<PathBuf>, _: String) -> Result<(), Error> {
+    Err(Error::Export(
+        "export requires the serde feature, build with `--features serde`".to_string(),
+    ))
+}
+
+fn render(
+    overrides: &Overrides,
+    PuzzleArgs { day, part, input }: PuzzleArgs,
+    output: PathBuf,
+    format: String,
+    scale: usize,
+    palette: Option<String>,
+    delay: u32,
+) -> Result<(), Error> {
+    let options = render::Options {
+        format: format.parse().map_err(Error::Render)?,
+        palette: palette
+            .map(|palette| palette.parse())
+            .transpose()
+            .map_err(Error::Render)?,
+        scale,
+        delay,
+    };
+    let input = read_input(day, input.as_deref())?;
+    let frames =
+        render::render(day, part, &input, overrides, &options, &output).map_err(|e| match e {
+            RenderError::Parse(e) => Error::Parse(e),
+            RenderError::Params(message) => Error::Params(message),
+            e => Error::Render(e.to_string()),
+        })?;
+
+    eprintln!("wrote {} frames to {}", frames, output.display());
+    Ok(())
+}
+
+/// overrides from the `--config` file, replaced by those given with `--param`.
+fn overrides(config: Option<PathBuf>, params: &[String]) -> Result<Overrides, Error> {
+    let mut overrides = match config {
+        Some(path) => Overrides::load(&path).map_err(Error::io("read", path.display()))?,
+        None => Overrides::default(),
+    };
+
+    let mut from_args = Overrides::default();
+    for param in params {
+        from_args.add(param).map_err(Error::Params)?;
+    }
+    overrides.extend(from_args);
+
+    Ok(overrides)
+}
+
+fn print_params(registry: &Registry, day: Option<u32>) -> Result<(), Error> {
+    let mut params = registry
+        .params()
+        .filter(|(d, _)| day.is_none_or(|day| *d == day))
+        .peekable();
+
+    if params.peek().is_none() {
+        return Err(Error::UnknownPuzzle(day.unwrap_or(0), 0));
+    }
+
+    for (day, values) in params {
+        print!("{}", Listing(day, values));
+    }
+    Ok(())
+}
+
+fn generate(day: u32, seed: u64, size: usize) -> Result<(), Error> {
+    let synthetic = synth::generate(day, seed, size).ok_or(Error::NoGenerator(day))?;
+
+    println!("{}", synthetic.input);
+    eprintln!("part 1: {}", synthetic.part1);
+    eprintln!("part 2: {}", synthetic.part2);
+    Ok(())
+}
+
+fn main() {
+    let options = Options::from_args();
+    let registry = overrides(options.config, &options.params).and_then(|overrides| {
+        let registry = Registry::with_overrides(&overrides).map_err(Error::Params)?;
+        Ok((overrides, registry))
+    });
+    let (overrides, registry) = match registry {
+        Ok(registry) => registry,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(e.exit_code());
+        }
+    };
+
+    let result = match options.command {
+        Command::Run { puzzle } => run(&registry, puzzle),
+        Command::RunAll {
+            threads,
+            answers,
+            timeout,
+        } => run_all(&registry, threads, answers, timeout),
+        Command::Verify {
+            day: Some(day),
+            part: Some(part),
+            input,
+            expect: Some(expect),
+            ..
+        } => verify_expected(&registry, PuzzleArgs { day, part, input }, expect),
+        Command::Verify {
+            day,
+            part,
+            input,
+            answers,
+            record,
+            ..
+        } => verify(&registry, day, part, input, answers, record),
+        Command::Bench {
+            day,
+            part,
+            input,
+            iterations,
+            format,
+        } => bench(&registry, day, part, input, iterations, format),
+        Command::Batch { dir, day, timeout } => run_batch(&registry, dir, day, timeout),
+        Command::Fetch {
+            day,
+            session,
+            base_url,
+            dir,
+        } => fetch(&registry, day, session, &base_url, dir),
+        Command::Submit {
+            puzzle,
+            session,
+            base_url,
+            history,
+        } => submit(&registry, puzzle, session, &base_url, history),
+        Command::Export { day, input, format } => export(day, input, format),
+        Command::Render {
+            puzzle,
+            output,
+            format,
+            scale,
+            palette,
+            delay,
+        } => render(&overrides, puzzle, output, format, scale, palette, delay),
+        Command::Params { day } => print_params(&registry, day),
+        Command::Generate { day, seed, size } => generate(day, seed, size),
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(e.exit_code());
+    }
+}
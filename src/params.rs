@@ -0,0 +1,292 @@
+//! Parameters of the solutions, e.g. to solve variants of the puzzles or the smaller examples.
+//!
+//! Every day with constants in its description has a parameter struct defaulting to the values of
+//! the puzzle. Overrides are given on the command line as `<day>.<key>=<value>` or in a file with
+//! one override per line:
+//!
+//! ```text
+//! # <day>.<key> = <value>
+//! 9.preamble = 5
+//! 7.bag = shiny gold
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Parameters of a single day.
+pub trait Params: Default {
+    /// sets the parameter `key` to the value parsed from `value`.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String>;
+
+    /// name and formatted value of every parameter, in a format [`set`](Params::set) accepts.
+    fn values(&self) -> Vec<(&'static str, String)>;
+
+    /// checks the combination of the parameters, once every override is set.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// parses the value of the parameter `key`.
+pub fn value<T>(key: &str, value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid value `{}` for {}: {}", value, key, e))
+}
+
+/// parses a range written as `<min>-<max>`, both inclusive.
+pub fn range<T>(key: &str, s: &str) -> Result<RangeInclusive<T>, String>
+where
+    T: FromStr + PartialOrd,
+    T::Err: Display,
+{
+    let (min, max) = s
+        .split_once('-')
+        .ok_or_else(|| format!("invalid range `{}` for {}, expected `<min>-<max>`", s, key))?;
+    let (min, max) = (value(key, min)?, value(key, max)?);
+
+    if min > max {
+        return Err(format!("invalid range `{}` for {}, min > max", s, key));
+    }
+
+    Ok(min..=max)
+}
+
+/// writes a range so [`range`] can parse it again.
+pub fn format_range<T: Display>(range: &RangeInclusive<T>) -> String {
+    format!("{}-{}", range.start(), range.end())
+}
+
+/// error for keys a day doesn't know.
+pub fn unknown(key: &str, known: &[(&str, String)]) -> String {
+    let known: Vec<&str> = known.iter().map(|(key, _)| *key).collect();
+
+    match known.as_slice() {
+        [] => format!("unknown parameter `{}`, the day has no parameters", key),
+        known => format!(
+            "unknown parameter `{}`, expected one of {}",
+            key,
+            known.join(", ")
+        ),
+    }
+}
+
+/// no parameters, for days without constants.
+impl Params for () {
+    fn set(&mut self, key: &str, _: &str) -> Result<(), String> {
+        Err(unknown(key, &[]))
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+}
+
+/// Overrides of the default parameters, keyed by day and parameter.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Overrides {
+    values: BTreeMap<(u32, String), String>,
+}
+
+impl Overrides {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(&path)?;
+
+        content.parse().map_err(|message| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.as_ref().display(), message),
+            )
+        })
+    }
+
+    /// adds an override written as `<day>.<key>=<value>`, replacing earlier ones of the same key.
+    pub fn add(&mut self, spec: &str) -> Result<(), String> {
+        let invalid = || {
+            format!(
+                "invalid parameter `{}`, expected `<day>.<key>=<value>`",
+                spec
+            )
+        };
+        let (name, value) = spec.split_once('=').ok_or_else(invalid)?;
+        let (day, key) = name.trim().split_once('.').ok_or_else(invalid)?;
+        let day = day.parse().map_err(|_| invalid())?;
+
+        self.values
+            .insert((day, key.to_string()), value.trim().to_string());
+        Ok(())
+    }
+
+    /// adds every override of `other`, replacing those of the same key.
+    pub fn extend(&mut self, other: Overrides) {
+        self.values.extend(other.values);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// days with at least one override.
+    pub fn days(&self) -> impl Iterator<Item = u32> + '_ {
+        let mut days: Vec<u32> = self.values.keys().map(|(day, _)| *day).collect();
+        days.dedup();
+        days.into_iter()
+    }
+
+    /// the default parameters of `day` with the overrides applied.
+    pub fn apply<P: Params>(&self, day: u32) -> Result<P, String> {
+        let mut params = P::default();
+
+        for ((_, key), value) in self
+            .values
+            .range((day, String::new())..(day + 1, String::new()))
+        {
+            params
+                .set(key, value)
+                .map_err(|message| format!("day {}: {}", day, message))?;
+        }
+        params
+            .validate()
+            .map_err(|message| format!("day {}: {}", day, message))?;
+
+        Ok(params)
+    }
+}
+
+impl FromStr for Overrides {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut overrides = Overrides::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            overrides
+                .add(line)
+                .map_err(|message| format!("line {}: {}", index + 1, message))?;
+        }
+
+        Ok(overrides)
+    }
+}
+
+/// [`values`](Params::values) of a day formatted as lines of `<day>.<key> = <value>`, i.e. in the
+/// format of an override file.
+pub struct Listing<'a>(pub u32, pub &'a [(&'static str, String)]);
+
+impl Display for Listing<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in self.1 {
+            writeln!(f, "{}.{} = {}", self.0, key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::params::*;
+
+    #[derive(Debug, Default, Eq, PartialEq)]
+    struct Example {
+        size: usize,
+        range: Option<RangeInclusive<u32>>,
+    }
+
+    impl Params for Example {
+        fn set(&mut self, key: &str, s: &str) -> Result<(), String> {
+            match key {
+                "size" => self.size = value(key, s)?,
+                "range" => self.range = Some(range(key, s)?),
+                _ => return Err(unknown(key, &self.values())),
+            }
+
+            Ok(())
+        }
+
+        fn values(&self) -> Vec<(&'static str, String)> {
+            vec![
+                ("size", self.size.to_string()),
+                (
+                    "range",
+                    self.range.as_ref().map(format_range).unwrap_or_default(),
+                ),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut overrides: Overrides = "# comment\n1.size = 3\n2.size=4\n1.range = 2-5\n"
+            .parse()
+            .unwrap();
+        overrides.add("1.size=7").unwrap();
+
+        assert_eq!(
+            Ok(Example {
+                size: 7,
+                range: Some(2..=5)
+            }),
+            overrides.apply(1)
+        );
+        assert_eq!(Ok(Example::default()), overrides.apply(3));
+    }
+
+    #[test]
+    fn test_errors() {
+        let apply = |spec: &str| {
+            let mut overrides = Overrides::default();
+            overrides.add(spec)?;
+            overrides.apply::<Example>(1)
+        };
+
+        assert_eq!(
+            Err("day 1: unknown parameter `x`, expected one of size, range".to_string()),
+            apply("1.x=1")
+        );
+        assert_eq!(
+            Err("day 1: invalid range `5-2` for range, min > max".to_string()),
+            apply("1.range=5-2")
+        );
+        assert!(apply("1.size=-1")
+            .unwrap_err()
+            .starts_with("day 1: invalid value `-1`"));
+        assert!(apply("size=1")
+            .unwrap_err()
+            .starts_with("invalid parameter"));
+        assert_eq!(
+            Err("day 1: unknown parameter `x`, the day has no parameters".to_string()),
+            "1.x = 1".parse::<Overrides>().unwrap().apply::<()>(1)
+        );
+        assert_eq!(
+            Err("line 2: invalid parameter `x`, expected `<day>.<key>=<value>`".to_string()),
+            "1.size = 1\nx".parse::<Overrides>()
+        );
+    }
+
+    #[test]
+    fn test_listing_can_be_parsed() {
+        let example = Example {
+            size: 2,
+            range: Some(1..=3),
+        };
+        let overrides: Overrides = Listing(4, &example.values()).to_string().parse().unwrap();
+
+        assert_eq!(Ok(example), overrides.apply(4));
+    }
+}
@@ -0,0 +1,266 @@
+//! Shared error handling for the input generators.
+//!
+//! Parsers of single lines or records report an [`Invalid`] which is positioned relative to the
+//! text they were given. The helpers in this module split the puzzle input, hand the pieces to
+//! those parsers and turn any [`Invalid`] into a [`ParseError`] that knows the day, line and column
+//! of the offending input.
+//!
+//! The parsed inputs borrow from the input text, so the helpers can't [`normalize`] it themselves.
+//! The runners normalize every input once before parsing it, so inputs saved on Windows or with
+//! trailing spaces parse like the original.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::str::FromStr;
+
+/// Describes what is wrong with a piece of input, positioned relative to the start of that piece.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Invalid {
+    line: usize,
+    column: usize,
+    width: usize,
+    message: String,
+}
+
+impl Invalid {
+    /// error at the given (zero based) column of the first line.
+    pub fn new(column: usize, message: impl Display) -> Self {
+        Self {
+            line: 0,
+            column,
+            width: 1,
+            message: message.to_string(),
+        }
+    }
+
+    /// error covering `fragment`, which has to be a subslice of `source`.
+    pub fn at(source: &str, fragment: &str, message: impl Display) -> Self {
+        let offset = (fragment.as_ptr() as usize)
+            .saturating_sub(source.as_ptr() as usize)
+            .min(source.len());
+        let before = &source[..offset];
+        let line = before.matches('\n').count();
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+        Self {
+            line,
+            column: before[line_start..].chars().count(),
+            width: fragment.chars().count().max(1),
+            message: message.to_string(),
+        }
+    }
+
+    /// moves the error by the position of the fragment it was reported for.
+    pub fn offset(mut self, line: usize, column: usize) -> Self {
+        if self.line == 0 {
+            self.column += column;
+        }
+        self.line += line;
+
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// attaches the error to the input of `day`. `first_line` is the zero based line index of
+    /// `source` within the whole input.
+    pub fn locate(self, day: u32, first_line: usize, source: &str) -> ParseError {
+        ParseError {
+            day,
+            line: first_line + self.line + 1,
+            column: self.column + 1,
+            width: self.width,
+            snippet: source
+                .lines()
+                .nth(self.line)
+                .unwrap_or_default()
+                .to_string(),
+            message: self.message,
+        }
+    }
+}
+
+/// Error returned by the input generators, pointing to the offending line and column.
+#[derive(Clone, Eq, PartialEq)]
+pub struct ParseError {
+    day: u32,
+    line: usize,
+    column: usize,
+    width: usize,
+    snippet: String,
+    message: String,
+}
+
+impl ParseError {
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// one based line number
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// one based column number, counted in characters
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// the offending line
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        writeln!(
+            f,
+            "day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.message
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width)
+        )
+    }
+}
+
+// cargo-aoc prints generator errors using `{:#?}`, so the debug representation is the report too.
+impl Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Error for ParseError {}
+
+/// parses `fragment` (a subslice of `source`) as a number.
+pub fn number<T>(source: &str, fragment: &str) -> Result<T, Invalid>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    fragment.parse().map_err(|e| {
+        Invalid::at(
+            source,
+            fragment,
+            format!("invalid number `{}`: {}", fragment, e),
+        )
+    })
+}
+
+/// removes a byte order mark, converts CRLF line endings to LF, trims trailing whitespace of every
+/// line and drops trailing blank lines. Doesn't copy inputs that are already normalized.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input).trim_end();
+
+    if input
+        .lines()
+        .all(|line| line.len() == line.trim_end().len())
+        && !input.contains('\r')
+    {
+        return Cow::Borrowed(input);
+    }
+
+    Cow::Owned(
+        input
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<&str>>()
+            .join("\n"),
+    )
+}
+
+/// parses every line of `input` using `parse`.
+pub fn lines<'a, T, F>(day: u32, input: &'a str, mut parse: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&'a str) -> Result<T, Invalid>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse(line).map_err(|e| e.locate(day, index, line)))
+        .collect()
+}
+
+/// parses every record of `input` using `parse`. Records are separated by a blank line.
+pub fn records<'a, T, F>(day: u32, input: &'a str, mut parse: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&'a str) -> Result<T, Invalid>,
+{
+    let mut first_line = 0;
+
+    input
+        .split("\n\n")
+        .map(|record| {
+            let result = parse(record).map_err(|e| e.locate(day, first_line, record));
+            first_line += record.matches('\n').count() + 2;
+
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::*;
+
+    #[test]
+    fn test_invalid_at() {
+        let source = "ab\ncd ef";
+        let invalid = Invalid::at(source, &source[6..], "bad");
+
+        assert_eq!((1, 3, 2), (invalid.line, invalid.column, invalid.width));
+    }
+
+    #[test]
+    fn test_records_report_line_and_column() {
+        let error = records(4, "a:1\nb:2\n\nc:3 d", |record| {
+            record
+                .split_whitespace()
+                .find(|field| !field.contains(':'))
+                .map_or(Ok(()), |field| Err(Invalid::at(record, field, "no value")))
+        })
+        .unwrap_err();
+
+        assert_eq!((4, 4, 5), (error.day(), error.line(), error.column()));
+        assert_eq!("c:3 d", error.snippet());
+        assert_eq!(
+            "day 4, line 4, column 5: no value\n  |\n4 | c:3 d\n  |     ^",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("a\nb\n"), Cow::Borrowed("a\nb")));
+        assert_eq!("a\n\nb", normalize("\u{feff}a \r\n\t\r\nb\r\n\r\n"));
+        assert_eq!("", normalize("\r\n"));
+    }
+
+    #[test]
+    fn test_lines_report_number_errors() {
+        let error = lines(1, "12\n3x4", |line| number::<u32>(line, line)).unwrap_err();
+
+        assert_eq!((2, 1), (error.line(), error.column()));
+        assert_eq!(
+            "invalid number `3x4`: invalid digit found in string",
+            error.message()
+        );
+    }
+}
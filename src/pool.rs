@@ -0,0 +1,60 @@
+//! Minimal thread pool for running independent solvers concurrently.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// number of threads to use if the user didn't choose one.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+/// applies `f` to every item using up to `threads` threads, returning the results in the order of
+/// `items`.
+pub fn map<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<Option<R>>>());
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let item = match items.get(index) {
+                    Some(item) => item,
+                    None => break,
+                };
+
+                let result = f(item);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every item is processed"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::*;
+
+    #[test]
+    fn test_map_keeps_order() {
+        let items: Vec<u32> = (0..100).collect();
+
+        assert_eq!(
+            items.iter().map(|x| x * 2).collect::<Vec<u32>>(),
+            map(&items, 8, |x| x * 2)
+        );
+        assert!(map(&[] as &[u32], 8, |x| *x).is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+//! Timing of the generators and solvers over repeated runs.
+
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Runner;
+use std::fmt::{self, Display, Write as _};
+use std::time::Duration;
+
+/// Distribution of the durations of repeated runs.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// `samples` must not be empty.
+    pub fn of(samples: &mut [Duration]) -> Self {
+        samples.sort_unstable();
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+
+    fn to_json(self) -> String {
+        format!(
+            r#"{{"min_ns": {}, "median_ns": {}, "max_ns": {}}}"#,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.max.as_nanos()
+        )
+    }
+}
+
+/// Timings of a single part, split into the time spent in the generator and in the solver.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub day: u32,
+    pub part: u32,
+    pub answer: Answer,
+    pub repetitions: u32,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// parses `input` and solves the part of `runner` `repetitions` times.
+pub fn profile(runner: &dyn Runner, input: &str, repetitions: u32) -> Result<Profile, ParseError> {
+    let repetitions = repetitions.max(1);
+    let mut parse_times = Vec::with_capacity(repetitions as usize);
+    let mut solve_times = Vec::with_capacity(repetitions as usize);
+    let mut answer = None;
+
+    for _ in 0..repetitions {
+        let run = runner.run(input)?;
+
+        parse_times.push(run.parse_time);
+        solve_times.push(run.solve_time);
+        answer = Some(run.answer);
+    }
+
+    Ok(Profile {
+        day: runner.day(),
+        part: runner.part(),
+        answer: answer.expect("solved at least once"),
+        repetitions,
+        parse: Stats::of(&mut parse_times),
+        solve: Stats::of(&mut solve_times),
+    })
+}
+
+/// Human readable table of profiles.
+pub struct Table<'a>(pub &'a [Profile]);
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day part {:>12} {:>12} {:>12}   {:>12} {:>12} {:>12}",
+            "parse min", "median", "max", "solve min", "median", "max"
+        )?;
+
+        for profile in self.0 {
+            let column = |duration: Duration| format!("{:>12}", format!("{:.2?}", duration));
+
+            writeln!(
+                f,
+                "{:>3} {:>4} {} {} {}   {} {} {}",
+                profile.day,
+                profile.part,
+                column(profile.parse.min),
+                column(profile.parse.median),
+                column(profile.parse.max),
+                column(profile.solve.min),
+                column(profile.solve.median),
+                column(profile.solve.max),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+/// machine readable representation of `profiles`, durations are given in nanoseconds.
+pub fn to_json(profiles: &[Profile]) -> String {
+    let mut json = String::from("[\n");
+
+    for (index, profile) in profiles.iter().enumerate() {
+        let separator = if index + 1 < profiles.len() { "," } else { "" };
+
+        // writing into a String can't fail
+        let _ = writeln!(
+            json,
+            r#"  {{"day": {}, "part": {}, "answer": {}, "repetitions": {}, "parse": {}, "solve": {}}}{}"#,
+            profile.day,
+            profile.part,
+            json_string(&profile.answer.to_string()),
+            profile.repetitions,
+            profile.parse.to_json(),
+            profile.solve.to_json(),
+            separator
+        );
+    }
+
+    json.push(']');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::profile::*;
+
+    #[test]
+    fn test_stats() {
+        let mut samples = [4, 1, 3, 2].map(Duration::from_millis);
+
+        assert_eq!(
+            Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_micros(2500),
+                max: Duration::from_millis(4),
+            },
+            Stats::of(&mut samples)
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(r#""a\"b\\c\nd""#, json_string("a\"b\\c\nd"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Lookup of the solutions at runtime.
+
+use crate::params::{Overrides, Params};
+use crate::solution::{self, Runner, Solution};
+use crate::{day1, day10, day11, day2, day3, day4, day5, day6, day7, day8, day9};
+use std::collections::BTreeMap;
+
+/// Runners of the registered solutions, keyed by day and part.
+pub struct Registry {
+    runners: BTreeMap<(u32, u32), Box<dyn Runner>>,
+    params: BTreeMap<u32, Vec<(&'static str, String)>>,
+}
+
+impl Registry {
+    /// creates an empty registry. Use `Registry::default()` to get one containing every day.
+    pub fn new() -> Self {
+        Registry {
+            runners: BTreeMap::new(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// registry containing every day, using the default parameters with `overrides` applied.
+    pub fn with_overrides(overrides: &Overrides) -> Result<Self, String> {
+        let mut registry = Self::new();
+
+        registry.configure(1, overrides, |params| day1::Day1 { params })?;
+        registry.configure(2, overrides, |()| day2::Day2)?;
+        registry.configure(3, overrides, |params| day3::Day3 { params })?;
+        registry.configure(4, overrides, |params| day4::Day4 { params })?;
+        registry.configure(5, overrides, |params| day5::Day5 { params })?;
+        registry.configure(6, overrides, |()| day6::Day6)?;
+        registry.configure(7, overrides, |params| day7::Day7 { params })?;
+        registry.configure(8, overrides, |()| day8::Day8)?;
+        registry.configure(9, overrides, |params| day9::Day9 { params })?;
+        registry.configure(10, overrides, |params| day10::Day10 { params })?;
+        registry.configure(11, overrides, |params| day11::Day11 { params })?;
+
+        match overrides
+            .days()
+            .find(|day| !registry.params.contains_key(day))
+        {
+            Some(day) => Err(format!("day {}: no solution to apply parameters to", day)),
+            None => Ok(registry),
+        }
+    }
+
+    /// registers the solution of `day` created from its parameters with `overrides` applied.
+    fn configure<P, S>(
+        &mut self,
+        day: u32,
+        overrides: &Overrides,
+        solution: impl FnOnce(P) -> S,
+    ) -> Result<(), String>
+    where
+        P: Params,
+        S: Solution + Send + Sync + 'static,
+    {
+        let params: P = overrides.apply(day)?;
+
+        self.params.insert(day, params.values());
+        self.register(solution(params));
+        Ok(())
+    }
+
+    /// registers both parts of `solution`, replacing previously registered ones of the same day.
+    pub fn register<S>(&mut self, solution: S)
+    where
+        S: Solution + Send + Sync + 'static,
+    {
+        for runner in solution::runners(solution) {
+            self.runners.insert((runner.day(), runner.part()), runner);
+        }
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&dyn Runner> {
+        self.runners.get(&(day, part)).map(Box::as_ref)
+    }
+
+    /// all runners, ordered by day and part.
+    pub fn runners(&self) -> impl Iterator<Item = &dyn Runner> {
+        self.runners.values().map(Box::as_ref)
+    }
+
+    /// runners of every part of `day`.
+    pub fn day(&self, day: u32) -> impl Iterator<Item = &dyn Runner> {
+        self.runners
+            .range((day, 0)..=(day, u32::MAX))
+            .map(|(_, runner)| runner.as_ref())
+    }
+
+    /// name and value of every parameter of the days registered by
+    /// [`with_overrides`](Registry::with_overrides), ordered by day.
+    pub fn params(&self) -> impl Iterator<Item = (u32, &[(&'static str, String)])> {
+        self.params
+            .iter()
+            .map(|(day, values)| (*day, values.as_slice()))
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::with_overrides(&Overrides::default()).expect("the default parameters are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::*;
+    use crate::Answer;
+
+    #[test]
+    fn test_every_day_is_registered() {
+        let registry = Registry::default();
+        let keys: Vec<(u32, u32)> = registry
+            .runners()
+            .map(|runner| (runner.day(), runner.part()))
+            .collect();
+
+        assert_eq!(22, keys.len());
+        assert_eq!((11, 2), keys[21]);
+        assert_eq!(2, registry.day(10).count());
+        assert_eq!(
+            Answer::from(514579),
+            registry
+                .get(1, 1)
+                .unwrap()
+                .solve("1721\n979\n366\n299\n675\n1456")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_overrides() {
+        let example = "35\n20\n15\n25\n47\n40\n62\n55\n65\n95\n102\n117\n150\n182\n127";
+        let overrides: Overrides = "9.preamble = 5\n1.sum = 10".parse().unwrap();
+        let registry = Registry::with_overrides(&overrides).unwrap();
+
+        assert_eq!(
+            Answer::from(127),
+            registry.get(9, 1).unwrap().solve(example).unwrap()
+        );
+        assert_eq!(
+            Answer::from(21),
+            registry.get(1, 1).unwrap().solve("3\n7\n9").unwrap()
+        );
+
+        let params: Vec<(u32, &[(&str, String)])> = registry.params().collect();
+        assert_eq!(11, params.len());
+        assert_eq!((9, &[("preamble", "5".to_string())][..]), params[8]);
+        assert!(params[1].1.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        let registry = |overrides: &str| Registry::with_overrides(&overrides.parse().unwrap());
+
+        assert_eq!(
+            Some(
+                "day 9: invalid value `x` for preamble: invalid digit found in string".to_string()
+            ),
+            registry("9.preamble = x").err()
+        );
+        assert_eq!(
+            Some("day 2: unknown parameter `min`, the day has no parameters".to_string()),
+            registry("2.min = 1").err()
+        );
+        assert_eq!(
+            Some("day 12: no solution to apply parameters to".to_string()),
+            registry("12.size = 1").err()
+        );
+    }
+}
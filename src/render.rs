@@ -0,0 +1,538 @@
+//! Renders the grids of the puzzles as images, e.g. every generation of the seats of day 11 to
+//! watch them settle.
+//!
+//! A frame is a [`Grid`] holding an index into a [`Palette`] per cell. Every cell becomes a square
+//! of `scale` pixels when the frame is written. PPM works out of the box, PNG and animated GIF need
+//! the `images` feature.
+
+use crate::grid::Grid;
+use crate::params::Overrides;
+use crate::parse::{self, ParseError};
+use crate::{day11, day3};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// red, green and blue.
+pub type Color = [u8; 3];
+
+/// Colors of the palette indices in the frames.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Palette(Vec<Color>);
+
+impl Palette {
+    /// the largest palette the indices of a frame can address.
+    pub const MAX_COLORS: usize = 256;
+
+    pub fn colors(&self) -> &[Color] {
+        &self.0
+    }
+
+    /// checks that every cell of `frame` has a color.
+    fn check(&self, frame: &Grid<u8>) -> Result<(), RenderError> {
+        match frame.cells().iter().max() {
+            Some(index) if *index as usize >= self.0.len() => Err(RenderError::Palette {
+                index: *index,
+                colors: self.0.len(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// parses comma separated `#rrggbb` colors, e.g. `#000000,#ffffff`.
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let color = |s: &str| -> Result<Color, String> {
+            let hex = s.trim().strip_prefix('#').unwrap_or(s.trim());
+            let invalid = || format!("invalid color `{}`, expected `#rrggbb`", s);
+
+            if hex.len() != 6 || !hex.is_ascii() {
+                return Err(invalid());
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+            Ok([channel(0)?, channel(2)?, channel(4)?])
+        };
+
+        let colors = s.split(',').map(color).collect::<Result<Vec<_>, _>>()?;
+        if colors.len() > Self::MAX_COLORS {
+            return Err(format!(
+                "a palette has at most {} colors, found {}",
+                Self::MAX_COLORS,
+                colors.len()
+            ));
+        }
+
+        Ok(Palette(colors))
+    }
+}
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, [r, g, b]) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "#{:02x}{:02x}{:02x}", r, g, b)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    /// a binary ppm file per frame
+    Ppm,
+    /// a png file per frame
+    #[cfg(feature = "images")]
+    Png,
+    /// a single animated gif
+    #[cfg(feature = "images")]
+    Gif,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(Format::Ppm),
+            #[cfg(feature = "images")]
+            "png" => Ok(Format::Png),
+            #[cfg(feature = "images")]
+            "gif" => Ok(Format::Gif),
+            #[cfg(not(feature = "images"))]
+            "png" | "gif" => Err(format!(
+                "{} requires the images feature, build with `--features images`",
+                s
+            )),
+            _ => Err(format!(
+                "unknown format `{}`, expected `ppm`, `png` or `gif`",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Options {
+    pub format: Format,
+    /// colors replacing the palette of the day
+    pub palette: Option<Palette>,
+    /// width and height of a cell in pixels
+    pub scale: usize,
+    /// milliseconds between the frames of a gif
+    pub delay: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            format: Format::Ppm,
+            palette: None,
+            scale: 4,
+            delay: 100,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    Parse(ParseError),
+    Params(String),
+    UnknownPuzzle(u32, u32),
+    Palette {
+        index: u8,
+        colors: usize,
+    },
+    ZeroScale,
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+    FrameSize,
+    Io(PathBuf, io::Error),
+    #[cfg(feature = "images")]
+    Png(png::EncodingError),
+    #[cfg(feature = "images")]
+    Gif(PathBuf, gif::EncodingError),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::Parse(e) => write!(f, "failed to parse the input:\n{}", e),
+            RenderError::Params(message) => write!(f, "invalid parameters: {}", message),
+            RenderError::UnknownPuzzle(day, part) => write!(
+                f,
+                "can't render day {} part {}, only days 3 and 11 can be rendered",
+                day, part
+            ),
+            RenderError::Palette { index, colors } => write!(
+                f,
+                "the frame uses color {} but the palette has only {} colors",
+                index, colors
+            ),
+            RenderError::ZeroScale => write!(f, "the scale must be at least 1"),
+            RenderError::TooLarge { width, height } => write!(
+                f,
+                "a frame of {}x{} pixels is too large for the format",
+                width, height
+            ),
+            RenderError::FrameSize => write!(f, "the frames of a gif must be of the same size"),
+            RenderError::Io(path, e) => write!(f, "failed to write {}: {}", path.display(), e),
+            #[cfg(feature = "images")]
+            RenderError::Png(e) => write!(f, "failed to encode png: {}", e),
+            #[cfg(feature = "images")]
+            RenderError::Gif(path, e) => write!(f, "failed to write {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+impl From<ParseError> for RenderError {
+    fn from(e: ParseError) -> Self {
+        RenderError::Parse(e)
+    }
+}
+
+/// palette indices of the pixels row by row, every cell a square of `scale` pixels.
+fn pixels(frame: &Grid<u8>, scale: usize) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(frame.cells().len() * scale * scale);
+
+    for row in frame.rows() {
+        let line: Vec<u8> = row
+            .iter()
+            .flat_map(|cell| iter::repeat_n(*cell, scale))
+            .collect();
+        for _ in 0..scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+
+    pixels
+}
+
+/// size of `frame` in pixels, if it fits into `max` on both axes.
+fn size(frame: &Grid<u8>, scale: usize, max: usize) -> Result<(usize, usize), RenderError> {
+    let (width, height) = (frame.width(), frame.height());
+
+    match (width.checked_mul(scale), height.checked_mul(scale)) {
+        (Some(w), Some(h)) if w <= max && h <= max => Ok((w, h)),
+        _ => Err(RenderError::TooLarge {
+            width: width.saturating_mul(scale),
+            height: height.saturating_mul(scale),
+        }),
+    }
+}
+
+/// `frame` as binary ppm (`P6`).
+fn ppm(frame: &Grid<u8>, palette: &Palette, scale: usize) -> Result<Vec<u8>, RenderError> {
+    palette.check(frame)?;
+    let (width, height) = size(frame, scale, usize::MAX)?;
+
+    let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    ppm.reserve(width * height * 3);
+    for pixel in pixels(frame, scale) {
+        ppm.extend_from_slice(&palette.0[pixel as usize]);
+    }
+
+    Ok(ppm)
+}
+
+/// `frame` as png with a color palette.
+#[cfg(feature = "images")]
+fn png(frame: &Grid<u8>, palette: &Palette, scale: usize) -> Result<Vec<u8>, RenderError> {
+    palette.check(frame)?;
+    let (width, height) = size(frame, scale, u32::MAX as usize)?;
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(palette.0.concat());
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels(frame, scale)))
+        .map_err(RenderError::Png)?;
+
+    Ok(png)
+}
+
+#[cfg(feature = "images")]
+type GifEncoder = gif::Encoder<io::BufWriter<fs::File>>;
+
+/// Writes frames as they are rendered, to numbered files in a directory or into a single gif.
+pub struct Recorder {
+    format: Format,
+    palette: Palette,
+    scale: usize,
+    #[cfg_attr(not(feature = "images"), allow(dead_code))]
+    delay: u32,
+    output: PathBuf,
+    frames: usize,
+    /// created with the first frame, which decides the size of the gif
+    #[cfg(feature = "images")]
+    gif: Option<(GifEncoder, (u16, u16))>,
+}
+
+impl Recorder {
+    /// recorder writing to `output`, the directory of the frames or the path of the gif.
+    pub fn new(
+        output: impl Into<PathBuf>,
+        options: &Options,
+        palette: Palette,
+    ) -> Result<Self, RenderError> {
+        if options.scale == 0 {
+            return Err(RenderError::ZeroScale);
+        }
+
+        Ok(Recorder {
+            format: options.format,
+            palette: options.palette.clone().unwrap_or(palette),
+            scale: options.scale,
+            delay: options.delay,
+            output: output.into(),
+            frames: 0,
+            #[cfg(feature = "images")]
+            gif: None,
+        })
+    }
+
+    /// number of frames written so far.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn record(&mut self, frame: &Grid<u8>) -> Result<(), RenderError> {
+        let (image, extension) = match self.format {
+            Format::Ppm => (ppm(frame, &self.palette, self.scale)?, "ppm"),
+            #[cfg(feature = "images")]
+            Format::Png => (png(frame, &self.palette, self.scale)?, "png"),
+            #[cfg(feature = "images")]
+            Format::Gif => return self.record_gif(frame),
+        };
+
+        if self.frames == 0 {
+            fs::create_dir_all(&self.output).map_err(|e| self.io_error(e))?;
+        }
+        let path = self
+            .output
+            .join(format!("frame-{:04}.{}", self.frames, extension));
+        fs::write(&path, image).map_err(|e| RenderError::Io(path, e))?;
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    #[cfg(feature = "images")]
+    fn record_gif(&mut self, frame: &Grid<u8>) -> Result<(), RenderError> {
+        self.palette.check(frame)?;
+        let (width, height) = size(frame, self.scale, u16::MAX as usize)?;
+        let size = (width as u16, height as u16);
+
+        let Recorder {
+            palette,
+            output,
+            gif,
+            ..
+        } = self;
+        let gif_error = |e| RenderError::Gif(output.clone(), e);
+        let (encoder, expected) = match gif {
+            Some(gif) => gif,
+            None => {
+                let file =
+                    fs::File::create(&output).map_err(|e| RenderError::Io(output.clone(), e))?;
+                let mut encoder = gif::Encoder::new(
+                    io::BufWriter::new(file),
+                    size.0,
+                    size.1,
+                    &palette.0.concat(),
+                )
+                .map_err(gif_error)?;
+                encoder
+                    .set_repeat(gif::Repeat::Infinite)
+                    .map_err(gif_error)?;
+                gif.insert((encoder, size))
+            }
+        };
+        if *expected != size {
+            return Err(RenderError::FrameSize);
+        }
+
+        let frame = gif::Frame {
+            width: size.0,
+            height: size.1,
+            // the delay of a gif is in hundredths of a second
+            delay: (self.delay / 10).min(u16::MAX as u32) as u16,
+            buffer: pixels(frame, self.scale).into(),
+            ..gif::Frame::default()
+        };
+        encoder.write_frame(&frame).map_err(gif_error)?;
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// completes the output, returns the number of frames.
+    pub fn finish(self) -> Result<usize, RenderError> {
+        #[cfg(feature = "images")]
+        if let Some((encoder, _)) = self.gif {
+            use std::io::Write;
+
+            let output = self.output;
+            encoder
+                .into_inner()
+                .and_then(|mut file| file.flush())
+                .map_err(|e| RenderError::Io(output, e))?;
+        }
+
+        Ok(self.frames)
+    }
+
+    fn io_error(&self, e: io::Error) -> RenderError {
+        RenderError::Io(self.output.clone(), e)
+    }
+}
+
+/// renders part `part` of `day` to `output`, with the parameters of the day taken from
+/// `overrides`. Returns the number of frames.
+///
+/// Day 3 renders the map with the path of every slope, day 11 every generation of the seats until
+/// they are stable or repeat.
+pub fn render(
+    day: u32,
+    part: u32,
+    input: &str,
+    overrides: &Overrides,
+    options: &Options,
+    output: &Path,
+) -> Result<usize, RenderError> {
+    let input = &*parse::normalize(input);
+
+    match (day, part) {
+        (3, 1) | (3, 2) => {
+            let map = day3::input_generator(input)?;
+            let params = overrides
+                .apply::<day3::Params>(day)
+                .map_err(RenderError::Params)?;
+            let slopes = match part {
+                1 => vec![params.slope],
+                _ => params.slopes,
+            };
+
+            let mut recorder = Recorder::new(output, options, day3::palette())?;
+            for frame in day3::frames(&map, &slopes) {
+                recorder.record(&frame)?;
+            }
+            recorder.finish()
+        }
+        (11, 1) | (11, 2) => {
+            let seats = day11::input_generator(input)?;
+            let params = overrides
+                .apply::<day11::Params>(day)
+                .map_err(RenderError::Params)?;
+
+            let mut recorder = Recorder::new(output, options, day11::palette())?;
+            // the automaton can't stop early, so the frames after an error are skipped
+            let mut result = Ok(());
+            let record = |frame: Grid<u8>| {
+                if result.is_ok() {
+                    result = recorder.record(&frame);
+                }
+            };
+            match part {
+                1 => day11::animate_part_1(&seats, &params, record),
+                _ => day11::animate_part_2(&seats, &params, record),
+            }
+            result?;
+            recorder.finish()
+        }
+        _ => Err(RenderError::UnknownPuzzle(day, part)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::*;
+
+    fn frame() -> Grid<u8> {
+        Grid::new(2, vec![0, 1, 1, 0])
+    }
+
+    fn palette() -> Palette {
+        "#000000,#ff8000".parse().unwrap()
+    }
+
+    #[test]
+    fn test_palette() {
+        assert_eq!(vec![[0, 0, 0], [255, 128, 0]], palette().colors());
+        assert_eq!("#000000,#ff8000", palette().to_string());
+        assert_eq!(Ok(palette()), "000000, #FF8000".parse());
+        assert!("#00000".parse::<Palette>().is_err());
+        assert!("#00000g".parse::<Palette>().is_err());
+        assert!("".parse::<Palette>().is_err());
+    }
+
+    #[test]
+    fn test_ppm() {
+        let ppm = ppm(&frame(), &palette(), 2).unwrap();
+        let (header, pixels) = ppm.split_at(11);
+
+        assert_eq!(b"P6\n4 4\n255\n", header);
+        assert_eq!(4 * 4 * 3, pixels.len());
+        // the first row is black, orange, the cells are two pixels wide
+        assert_eq!(&[0, 0, 0, 0, 0, 0, 255, 128, 0, 255, 128, 0], &pixels[..12]);
+        assert_eq!(&pixels[..12], &pixels[12..24]);
+    }
+
+    #[test]
+    fn test_colors_missing_from_palette() {
+        let palette: Palette = "#000000".parse().unwrap();
+
+        assert!(matches!(
+            ppm(&frame(), &palette, 1),
+            Err(RenderError::Palette {
+                index: 1,
+                colors: 1
+            })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "images")]
+    fn test_png() {
+        let png = png(&frame(), &palette(), 3).unwrap();
+
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+    }
+
+    #[test]
+    #[cfg(feature = "images")]
+    fn test_gif() {
+        let path = std::env::temp_dir().join(format!("aoc-render-{}.gif", std::process::id()));
+        let options = Options {
+            format: Format::Gif,
+            ..Options::default()
+        };
+
+        let mut recorder = Recorder::new(&path, &options, palette()).unwrap();
+        recorder.record(&frame()).unwrap();
+        recorder.record(&frame()).unwrap();
+        let frames = recorder.finish().unwrap();
+        let gif = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(2, frames);
+        assert_eq!(b"GIF89a", &gif[..6]);
+        assert_eq!(Some(&b';'), gif.last());
+    }
+}
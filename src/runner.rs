@@ -0,0 +1,13 @@
+//! Helpers for running the solutions on the puzzle inputs.
+
+pub const YEAR: u32 = 2020;
+
+/// directory of the puzzle inputs as used by cargo-aoc.
+pub fn default_input_dir() -> String {
+    format!("input/{}", YEAR)
+}
+
+/// path of the puzzle input of `day` as used by cargo-aoc.
+pub fn default_input_path(day: u32) -> String {
+    format!("{}/day{}.txt", default_input_dir(), day)
+}
@@ -0,0 +1,238 @@
+//! Common interface of the solutions of every day.
+
+use crate::answer::Answer;
+use crate::cancel::{self, Cancelled, Token};
+use crate::parse::{self, ParseError};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Solution of a single day.
+///
+/// The input type differs from day to day and may borrow from the input text. Use [`runners`] to
+/// get type erased runners for the individual parts.
+///
+/// `parse` is given a [normalized](parse::normalize) input.
+pub trait Solution {
+    type Input<'a>;
+
+    fn day(&self) -> u32;
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>, ParseError>;
+
+    fn part1(&self, input: &Self::Input<'_>) -> Answer;
+
+    fn part2(&self, input: &Self::Input<'_>) -> Answer;
+}
+
+/// Answer of a single run and the time spent parsing and solving.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Type erased part of a [`Solution`].
+pub trait Runner: Send + Sync {
+    fn day(&self) -> u32;
+
+    fn part(&self) -> u32;
+
+    /// parses `input` and solves the part.
+    fn run(&self, input: &str) -> Result<Run, ParseError>;
+
+    /// parses `input` without solving the part, e.g. to benchmark the generator.
+    fn parse(&self, input: &str) -> Result<(), ParseError>;
+
+    fn solve(&self, input: &str) -> Result<Answer, ParseError> {
+        self.run(input).map(|run| run.answer)
+    }
+}
+
+/// Result of running a single part on a single input.
+#[derive(Debug)]
+pub enum Outcome {
+    Solved(Run),
+    /// the input couldn't be read or parsed
+    Failed(String),
+    /// the solver panicked, which usually means it relies on a property of another input
+    Panicked(String),
+    /// the solver was cancelled after exceeding the time limit
+    TimedOut(Duration),
+}
+
+impl Outcome {
+    pub fn is_solved(&self) -> bool {
+        matches!(self, Outcome::Solved(_))
+    }
+}
+
+impl From<Result<Run, ParseError>> for Outcome {
+    fn from(result: Result<Run, ParseError>) -> Self {
+        match result {
+            Ok(run) => Outcome::Solved(run),
+            Err(e) => Outcome::Failed(e.to_string()),
+        }
+    }
+}
+
+/// runs `runner` on `input`, turning a panic of the solver into an outcome.
+///
+/// If there is a `limit`, the solver is cancelled once it runs longer. Solvers only notice that at
+/// a [`cancel::checkpoint`], those without one still run to completion.
+pub fn run_isolated(runner: &dyn Runner, input: &str, limit: Option<Duration>) -> Outcome {
+    let token = Token::new();
+    let (finished, done) = mpsc::channel::<()>();
+    let watchdog = limit.map(|limit| {
+        let token = token.clone();
+
+        thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = done.recv_timeout(limit) {
+                token.cancel();
+            }
+        })
+    });
+
+    let result = cancel::with_token(&token, || {
+        panic::catch_unwind(AssertUnwindSafe(|| runner.run(input)))
+    });
+
+    drop(finished);
+    if let Some(watchdog) = watchdog {
+        let _ = watchdog.join();
+    }
+
+    result.map(Outcome::from).unwrap_or_else(|payload| {
+        if payload.is::<Cancelled>() {
+            return Outcome::TimedOut(limit.unwrap_or_default());
+        }
+
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+
+        Outcome::Panicked(message)
+    })
+}
+
+struct Part<S> {
+    solution: Arc<S>,
+    part: u32,
+}
+
+impl<S> Runner for Part<S>
+where
+    S: Solution + Send + Sync,
+{
+    fn day(&self) -> u32 {
+        self.solution.day()
+    }
+
+    fn part(&self) -> u32 {
+        self.part
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        self.solution.parse(&parse::normalize(input)).map(drop)
+    }
+
+    fn run(&self, input: &str) -> Result<Run, ParseError> {
+        let start = Instant::now();
+        let input = parse::normalize(input);
+        let parsed = self.solution.parse(&input)?;
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        let answer = match self.part {
+            1 => self.solution.part1(&parsed),
+            _ => self.solution.part2(&parsed),
+        };
+
+        Ok(Run {
+            answer,
+            parse_time,
+            solve_time: start.elapsed(),
+        })
+    }
+}
+
+/// type erased runners for both parts of `solution`.
+pub fn runners<S>(solution: S) -> Vec<Box<dyn Runner>>
+where
+    S: Solution + Send + Sync + 'static,
+{
+    let solution = Arc::new(solution);
+
+    vec![
+        Box::new(Part {
+            solution: Arc::clone(&solution),
+            part: 1,
+        }),
+        Box::new(Part { solution, part: 2 }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::*;
+    use crate::Registry;
+
+    #[test]
+    fn test_panics_are_isolated() {
+        let registry = Registry::default();
+        let runner = registry.get(9, 1).unwrap();
+
+        assert!(matches!(
+            run_isolated(runner, "1\n2\n3", None),
+            Outcome::Panicked(_)
+        ));
+        assert!(matches!(
+            run_isolated(runner, "x", None),
+            Outcome::Failed(_)
+        ));
+    }
+
+    struct Forever;
+
+    impl Solution for Forever {
+        type Input<'a> = ();
+
+        fn day(&self) -> u32 {
+            26
+        }
+
+        fn parse(&self, _: &str) -> Result<Self::Input<'_>, ParseError> {
+            Ok(())
+        }
+
+        fn part1(&self, _: &Self::Input<'_>) -> Answer {
+            loop {
+                cancel::checkpoint();
+            }
+        }
+
+        fn part2(&self, _: &Self::Input<'_>) -> Answer {
+            Answer::Integer(2)
+        }
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let limit = Some(Duration::from_millis(10));
+        let runners = runners(Forever);
+
+        assert!(matches!(
+            run_isolated(runners[0].as_ref(), "", limit),
+            Outcome::TimedOut(_)
+        ));
+        assert!(matches!(
+            run_isolated(runners[1].as_ref(), "", limit),
+            Outcome::Solved(_)
+        ));
+    }
+}
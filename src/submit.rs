@@ -0,0 +1,415 @@
+//! Submitting answers without annoying the advent of code servers.
+//!
+//! Every attempt is stored in a plain text file with one attempt per line:
+//!
+//! ```text
+//! # time year day part feedback wait answer
+//! 1606800000 2020 1 1 too-high 60 926464
+//! ```
+//!
+//! `time` is the unix time of the submission and `wait` the number of seconds the server asked us
+//! to wait before the next one. The history is used to refuse answers that are known to be wrong
+//! and submissions during a cooldown.
+
+use crate::answer::Answer;
+use crate::runner::YEAR;
+use regex::Regex;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// location of the submission history
+pub const DEFAULT_PATH: &str = "submissions.txt";
+
+const FORMAT: &str = "<time> <year> <day> <part> <feedback> <wait> <answer>";
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Feedback {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// wrong, without telling whether the answer is too high or too low
+    Wrong,
+    /// the answer wasn't checked, because the last one was submitted too recently
+    TooSoon,
+    /// the part was already solved
+    Solved,
+    /// the page didn't contain any of the known messages
+    Unrecognized,
+}
+
+impl Feedback {
+    const NAMES: [(Feedback, &'static str); 7] = [
+        (Feedback::Correct, "correct"),
+        (Feedback::TooHigh, "too-high"),
+        (Feedback::TooLow, "too-low"),
+        (Feedback::Wrong, "wrong"),
+        (Feedback::TooSoon, "too-soon"),
+        (Feedback::Solved, "solved"),
+        (Feedback::Unrecognized, "unrecognized"),
+    ];
+
+    pub fn is_wrong(self) -> bool {
+        matches!(self, Feedback::TooHigh | Feedback::TooLow | Feedback::Wrong)
+    }
+}
+
+impl Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, name) = Feedback::NAMES
+            .iter()
+            .find(|(feedback, _)| feedback == self)
+            .expect("every feedback has a name");
+
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Feedback {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Feedback::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(feedback, _)| *feedback)
+            .ok_or_else(|| format!("unknown feedback `{}`", s))
+    }
+}
+
+/// Response page of a submission, reduced to the parts we care about.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Reply {
+    pub feedback: Feedback,
+    /// time to wait before submitting the next answer
+    pub wait: Option<Duration>,
+    /// text of the message on the page
+    pub message: String,
+}
+
+impl Reply {
+    pub fn parse(page: &str) -> Self {
+        lazy_static! {
+            static ref TAG: Regex = Regex::new("<[^>]*>").unwrap();
+            static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+            static ref MINUTES: Regex = Regex::new(r"wait (one|\d+) minutes?").unwrap();
+            static ref LEFT: Regex = Regex::new(r"(?:(\d+)m )?(\d+)s left to wait").unwrap();
+        }
+
+        let article = match (page.find("<article"), page.find("</article>")) {
+            (Some(start), Some(end)) if start < end => &page[start..end],
+            _ => page,
+        };
+        let message = TAG.replace_all(article, "");
+        let message = WHITESPACE.replace_all(message.trim(), " ").into_owned();
+
+        let feedback = if message.contains("That's the right answer") {
+            Feedback::Correct
+        } else if message.contains("your answer is too high") {
+            Feedback::TooHigh
+        } else if message.contains("your answer is too low") {
+            Feedback::TooLow
+        } else if message.contains("That's not the right answer") {
+            Feedback::Wrong
+        } else if message.contains("You gave an answer too recently") {
+            Feedback::TooSoon
+        } else if message.contains("Did you already complete it") {
+            Feedback::Solved
+        } else {
+            Feedback::Unrecognized
+        };
+
+        let number = |m: Option<regex::Match>| m.map_or(0, |m| m.as_str().parse().unwrap_or(1));
+        let wait = MINUTES
+            .captures(&message)
+            .map(|captures| Duration::from_secs(60 * number(captures.get(1))))
+            .or_else(|| {
+                LEFT.captures(&message).map(|captures| {
+                    Duration::from_secs(60 * number(captures.get(1)) + number(captures.get(2)))
+                })
+            });
+
+        Reply {
+            feedback,
+            wait,
+            message,
+        }
+    }
+}
+
+/// A submitted answer and the response of the server.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Attempt {
+    /// unix time of the submission in seconds
+    pub time: u64,
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub feedback: Feedback,
+    /// seconds to wait before submitting the next answer
+    pub wait: u64,
+    pub answer: Answer,
+}
+
+/// Reason for not submitting an answer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Refusal {
+    /// the part is already solved
+    Solved { answer: Answer },
+    /// the answer was submitted before or lies beyond an answer that was too high or too low
+    KnownWrong {
+        previous: Answer,
+        feedback: Feedback,
+    },
+    /// the answer is rendered output, which has to be read by a human
+    Rendered,
+    /// the server asked us to wait this long before submitting again
+    Cooldown(Duration),
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Solved { answer } => write!(f, "already solved, the answer is {}", answer),
+            Refusal::KnownWrong { previous, feedback } => match feedback {
+                Feedback::TooHigh => write!(f, "wrong answer, {} was already too high", previous),
+                Feedback::TooLow => write!(f, "wrong answer, {} was already too low", previous),
+                _ => write!(f, "{} was already submitted and is wrong", previous),
+            },
+            Refusal::Rendered => write!(f, "the answer is rendered, read it and submit it by hand"),
+            Refusal::Cooldown(wait) => write!(
+                f,
+                "the server asked to wait, try again in {}s",
+                wait.as_secs().max(1)
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    attempts: Vec<Attempt>,
+}
+
+impl History {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(&path)?;
+
+        content.parse().map_err(|message| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.as_ref().display(), message),
+            )
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    pub fn record(&mut self, attempt: Attempt) {
+        self.attempts.push(attempt);
+    }
+
+    /// checks whether `answer` may be submitted for `part` of `day` at unix time `now`.
+    ///
+    /// The cooldown applies to every puzzle, because the server tracks it per account.
+    pub fn check(&self, day: u32, part: u32, answer: &Answer, now: u64) -> Result<(), Refusal> {
+        let attempts = || {
+            self.attempts
+                .iter()
+                .filter(|a| a.year == YEAR && a.day == day && a.part == part)
+        };
+
+        if let Some(solved) = attempts().find(|a| a.feedback == Feedback::Correct) {
+            return Err(Refusal::Solved {
+                answer: solved.answer.clone(),
+            });
+        }
+
+        if answer.is_render() {
+            return Err(Refusal::Rendered);
+        }
+
+        let value = answer.as_integer();
+        let known_wrong = attempts().filter(|a| a.feedback.is_wrong()).find(|a| {
+            let previous = a.answer.as_integer();
+
+            a.answer == *answer
+                || match (a.feedback, value, previous) {
+                    (Feedback::TooHigh, Some(value), Some(previous)) => value >= previous,
+                    (Feedback::TooLow, Some(value), Some(previous)) => value <= previous,
+                    _ => false,
+                }
+        });
+        if let Some(attempt) = known_wrong {
+            return Err(Refusal::KnownWrong {
+                previous: attempt.answer.clone(),
+                feedback: attempt.feedback,
+            });
+        }
+
+        let until = self.attempts.iter().map(|a| a.time + a.wait).max();
+        match until {
+            Some(until) if until > now => Err(Refusal::Cooldown(Duration::from_secs(until - now))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FromStr for History {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut history = History::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || format!("line {}: expected `{}`", index + 1, FORMAT);
+            let fields: Vec<&str> = line.splitn(7, ' ').collect();
+            let field = |index: usize| fields.get(index).copied().ok_or_else(invalid);
+            let parse_error = |_| invalid();
+            let Ok(answer) = field(6)?.trim().parse();
+
+            history.record(Attempt {
+                time: field(0)?.parse().map_err(parse_error)?,
+                year: field(1)?.parse().map_err(parse_error)?,
+                day: field(2)?.parse().map_err(parse_error)?,
+                part: field(3)?.parse().map_err(parse_error)?,
+                feedback: field(4)?.parse().map_err(|_| invalid())?,
+                wait: field(5)?.parse().map_err(parse_error)?,
+                answer,
+            });
+        }
+
+        Ok(history)
+    }
+}
+
+impl Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# time year day part feedback wait answer")?;
+
+        for a in &self.attempts {
+            writeln!(
+                f,
+                "{} {} {} {} {} {} {}",
+                a.time,
+                a.year,
+                a.day,
+                a.part,
+                a.feedback,
+                a.wait,
+                a.answer.to_line()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Summary of a submission as printed by the runner.
+pub struct Submission<'a>(pub &'a Attempt);
+
+impl Display for Submission<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let attempt = self.0;
+        write!(
+            f,
+            "day {:>2} part {}: {} {}",
+            attempt.day, attempt.part, attempt.answer, attempt.feedback
+        )?;
+
+        if attempt.wait > 0 {
+            write!(f, ", wait {}s before the next submission", attempt.wait)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::submit::*;
+
+    fn attempt(day: u32, feedback: Feedback, answer: &str) -> Attempt {
+        Attempt {
+            time: 1000,
+            year: YEAR,
+            day,
+            part: 1,
+            feedback,
+            wait: 60,
+            answer: answer.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut history = History::default();
+        history.record(attempt(1, Feedback::TooHigh, "926464"));
+        history.record(attempt(1, Feedback::Correct, "514579"));
+
+        let parsed: History = history.to_string().parse().unwrap();
+
+        assert_eq!(history.attempts(), parsed.attempts());
+    }
+
+    #[test]
+    fn test_check() {
+        let mut history = History::default();
+        history.record(attempt(1, Feedback::TooHigh, "100"));
+        history.record(attempt(1, Feedback::TooLow, "10"));
+        history.record(attempt(1, Feedback::Wrong, "abc"));
+        history.record(attempt(2, Feedback::Correct, "7"));
+
+        let known_wrong = |previous: &str, feedback| {
+            Err(Refusal::KnownWrong {
+                previous: previous.parse().unwrap(),
+                feedback,
+            })
+        };
+        let check =
+            |day, part, answer: &str, now| history.check(day, part, &answer.parse().unwrap(), now);
+
+        assert_eq!(
+            known_wrong("100", Feedback::TooHigh),
+            check(1, 1, "100", 2000)
+        );
+        assert_eq!(
+            known_wrong("100", Feedback::TooHigh),
+            check(1, 1, "101", 2000)
+        );
+        assert_eq!(known_wrong("10", Feedback::TooLow), check(1, 1, "3", 2000));
+        assert_eq!(
+            known_wrong("abc", Feedback::Wrong),
+            check(1, 1, "abc", 2000)
+        );
+        assert_eq!(
+            Err(Refusal::Solved {
+                answer: Answer::from(7)
+            }),
+            check(2, 1, "8", 2000)
+        );
+
+        assert_eq!(Ok(()), check(1, 1, "50", 2000));
+        assert_eq!(Ok(()), check(1, 2, "100", 2000));
+        assert_eq!(
+            Err(Refusal::Cooldown(Duration::from_secs(10))),
+            check(3, 1, "1", 1050)
+        );
+        assert_eq!(Err(Refusal::Rendered), check(1, 1, "#.\\n.#", 2000));
+    }
+}
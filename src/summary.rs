@@ -0,0 +1,119 @@
+//! Status of every part after running all of them.
+
+use crate::answers::{Answers, Key, Verdict};
+use crate::solution::Outcome;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Status {
+    Ok,
+    /// solved, but there is no known answer for the input
+    Unknown,
+    Wrong,
+    /// the input couldn't be read or parsed
+    Failed,
+    Panicked,
+    TimedOut,
+}
+
+impl Status {
+    pub const ALL: [Status; 6] = [
+        Status::Ok,
+        Status::Unknown,
+        Status::Wrong,
+        Status::Failed,
+        Status::Panicked,
+        Status::TimedOut,
+    ];
+
+    /// status of `outcome`, judging its answer by the known answer of `key`.
+    pub fn of(outcome: &Outcome, answers: &Answers, key: &Key) -> Self {
+        match outcome {
+            Outcome::Solved(run) => match answers.judge(key, &run.answer) {
+                Verdict::Correct => Status::Ok,
+                Verdict::Unknown => Status::Unknown,
+                Verdict::Wrong { .. } => Status::Wrong,
+            },
+            Outcome::Failed(_) => Status::Failed,
+            Outcome::Panicked(_) => Status::Panicked,
+            Outcome::TimedOut(_) => Status::TimedOut,
+        }
+    }
+
+    pub fn is_failure(self) -> bool {
+        !matches!(self, Status::Ok | Status::Unknown)
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Status::Ok => "ok",
+            Status::Unknown => "unknown",
+            Status::Wrong => "wrong",
+            Status::Failed => "failed",
+            Status::Panicked => "panicked",
+            Status::TimedOut => "timed out",
+        };
+
+        // pad, so the status can be used in tables
+        f.pad(status)
+    }
+}
+
+/// Table of the status of every part per day, followed by the number of parts per status.
+pub struct Summary<'a>(pub &'a [(u32, u32, Status)]);
+
+impl Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the statuses are ordered by part
+        let mut days: BTreeMap<u32, Vec<Status>> = BTreeMap::new();
+        for (day, _, status) in self.0 {
+            days.entry(*day).or_default().push(*status);
+        }
+
+        writeln!(f, "day  part 1     part 2")?;
+        for (day, statuses) in days {
+            let row: String = statuses
+                .iter()
+                .map(|status| format!("  {:<9}", status))
+                .collect();
+
+            writeln!(f, "{:>3}{}", day, row.trim_end())?;
+        }
+
+        let totals: Vec<String> = Status::ALL
+            .iter()
+            .map(|status| {
+                let count = self.0.iter().filter(|(_, _, s)| s == status).count();
+                format!("{} {}", count, status)
+            })
+            .collect();
+
+        writeln!(f, "{}", totals.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::summary::*;
+
+    #[test]
+    fn test_summary() {
+        let statuses = [
+            (1, 1, Status::Ok),
+            (1, 2, Status::Wrong),
+            (2, 1, Status::Panicked),
+            (2, 2, Status::TimedOut),
+        ];
+
+        assert_eq!(
+            "day  part 1     part 2\n  \
+               1  ok         wrong\n  \
+               2  panicked   timed out\n\
+             1 ok, 0 unknown, 1 wrong, 0 failed, 1 panicked, 1 timed out\n",
+            Summary(&statuses).to_string()
+        );
+    }
+}
@@ -0,0 +1,727 @@
+//! Deterministic generators of synthetic puzzle inputs with known answers.
+//!
+//! Every generator takes a seed and a size and always produces the same input for the same
+//! arguments, so generated inputs can be shared by passing around the seed instead of the input.
+//! The answers are planted while generating the input, or computed from its structure where
+//! planting isn't possible (e.g. the stable seat layout of day 11).
+
+use crate::answer::Answer;
+use crate::automaton::{Adjacent, Automaton, Life, Outcome, Visible};
+use crate::day11::State;
+use crate::grid::Grid;
+use std::collections::{BTreeSet, HashSet};
+use std::ops::Range;
+
+/// SplitMix64 pseudo random number generator.
+///
+/// Implemented here instead of using a crate, to guarantee the generated inputs never change.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    /// index below `len`, which must not be zero.
+    pub fn below(&mut self, len: usize) -> usize {
+        self.range(0..len as u64) as usize
+    }
+
+    /// `true` with a probability of `percent`%.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(0..100) < percent
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A generated puzzle input and the answers of both parts.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Synthetic {
+    pub input: String,
+    pub part1: Answer,
+    pub part2: Answer,
+}
+
+/// generates an input for `day`, returns `None` for days without a generator.
+pub fn generate(day: u32, seed: u64, size: usize) -> Option<Synthetic> {
+    let generator = match day {
+        1 => day1,
+        2 => day2,
+        3 => day3,
+        4 => day4,
+        5 => day5,
+        6 => day6,
+        7 => day7,
+        8 => day8,
+        9 => day9,
+        10 => day10,
+        11 => day11,
+        _ => return None,
+    };
+
+    Some(generator(seed, size))
+}
+
+/// `size` expense report entries, containing exactly one pair and one triple summing to 2020.
+pub fn day1(seed: u64, size: usize) -> Synthetic {
+    const SUM: u64 = 2020;
+    let mut rng = Rng::new(seed);
+
+    let a = rng.range(1..SUM / 2);
+    let (c, d, e) = loop {
+        let c = rng.range(1..SUM / 3);
+        let d = rng.range(c + 1..(SUM - c).div_ceil(2));
+        let e = SUM - c - d;
+
+        if ![c, d, e].contains(&a) && ![c, d, e].contains(&(SUM - a)) {
+            break (c, d, e);
+        }
+    };
+
+    let mut numbers = vec![a, SUM - a, c, d, e];
+    let mut values: HashSet<u64> = numbers.iter().copied().collect();
+    let mut pair_sums: HashSet<u64> = numbers
+        .iter()
+        .enumerate()
+        .flat_map(|(i, x)| numbers[i + 1..].iter().map(move |y| x + y))
+        .collect();
+
+    // numbers above 2020 never take part in a sum, so there's always room for more numbers
+    let max = 2 * SUM + 2 * size as u64;
+    while numbers.len() < size {
+        let x = rng.range(1..max);
+        let creates_sum =
+            x < SUM && (values.contains(&(SUM - x)) || pair_sums.contains(&(SUM - x)));
+
+        if values.contains(&x) || creates_sum {
+            continue;
+        }
+
+        pair_sums.extend(numbers.iter().map(|y| x + y));
+        values.insert(x);
+        numbers.push(x);
+    }
+
+    rng.shuffle(&mut numbers);
+
+    Synthetic {
+        input: join_lines(numbers),
+        part1: (a * (SUM - a)).into(),
+        part2: (c * d * e).into(),
+    }
+}
+
+/// `size` password policies and passwords.
+pub fn day2(seed: u64, size: usize) -> Synthetic {
+    let mut rng = Rng::new(seed);
+    let (mut valid_count, mut valid_position) = (0, 0);
+
+    let lines: Vec<String> = (0..size)
+        .map(|_| {
+            let one = rng.range(1..10) as usize;
+            let two = one + rng.range(1..10) as usize;
+            let character = (b'a' + rng.range(0..5) as u8) as char;
+            let password: String = (0..two + rng.below(5))
+                .map(|_| (b'a' + rng.range(0..5) as u8) as char)
+                .collect();
+
+            let count = password.chars().filter(|c| *c == character).count();
+            let at = |position: usize| password.as_bytes()[position - 1] == character as u8;
+
+            valid_count += (one..=two).contains(&count) as usize;
+            valid_position += (at(one) ^ at(two)) as usize;
+
+            format!("{}-{} {}: {}", one, two, character, password)
+        })
+        .collect();
+
+    Synthetic {
+        input: join_lines(lines),
+        part1: valid_count.into(),
+        part2: valid_position.into(),
+    }
+}
+
+/// map of `size` rows, 31 squares wide.
+pub fn day3(seed: u64, size: usize) -> Synthetic {
+    const WIDTH: usize = 31;
+    let mut rng = Rng::new(seed);
+
+    let rows: Vec<Vec<bool>> = (0..size)
+        .map(|_| (0..WIDTH).map(|_| rng.chance(25)).collect())
+        .collect();
+    let trees = |right: usize, down: usize| {
+        (0..size)
+            .step_by(down)
+            .filter(|y| rows[*y][(y / down * right) % WIDTH])
+            .count()
+    };
+    let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+    Synthetic {
+        input: join_lines(rows.iter().map(|row| {
+            row.iter()
+                .map(|tree| if *tree { '#' } else { '.' })
+                .collect::<String>()
+        })),
+        part1: trees(3, 1).into(),
+        part2: slopes
+            .iter()
+            .map(|(right, down)| trees(*right, *down))
+            .product::<usize>()
+            .into(),
+    }
+}
+
+/// `size` passports, each either valid, complete with one invalid field or missing a field.
+pub fn day4(seed: u64, size: usize) -> Synthetic {
+    const REQUIRED: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+    let mut rng = Rng::new(seed);
+    let (mut complete, mut valid) = (0, 0);
+
+    let passports: Vec<String> = (0..size)
+        .map(|_| {
+            let kind = rng.below(3);
+            let broken = rng.below(REQUIRED.len());
+
+            let mut fields: Vec<String> = REQUIRED
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| kind != 2 || *index != broken)
+                .map(|(index, key)| {
+                    let value = passport_value(&mut rng, key, kind == 1 && index == broken);
+                    format!("{}:{}", key, value)
+                })
+                .collect();
+
+            if rng.chance(50) {
+                fields.push(format!("cid:{}", rng.range(1..1000)));
+            }
+            rng.shuffle(&mut fields);
+
+            complete += (kind != 2) as usize;
+            valid += (kind == 0) as usize;
+
+            fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| match index {
+                    0 => field.to_string(),
+                    _ if rng.chance(30) => format!("\n{}", field),
+                    _ => format!(" {}", field),
+                })
+                .collect::<String>()
+        })
+        .collect();
+
+    Synthetic {
+        input: passports.join("\n\n"),
+        part1: complete.into(),
+        part2: valid.into(),
+    }
+}
+
+fn passport_value(rng: &mut Rng, key: &str, invalid: bool) -> String {
+    let year = |rng: &mut Rng, min: u64, max: u64| match invalid {
+        false => rng.range(min..max + 1).to_string(),
+        true if rng.chance(50) => rng.range(1900..min).to_string(),
+        true => rng.range(max + 1..2100).to_string(),
+    };
+
+    match (key, invalid) {
+        ("byr", _) => year(rng, 1920, 2002),
+        ("iyr", _) => year(rng, 2010, 2020),
+        ("eyr", _) => year(rng, 2020, 2030),
+        ("hgt", false) if rng.chance(50) => format!("{}cm", rng.range(150..194)),
+        ("hgt", false) => format!("{}in", rng.range(59..77)),
+        ("hgt", true) => match rng.below(3) {
+            0 => format!("{}cm", rng.range(194..250)),
+            1 => format!("{}in", rng.range(20..59)),
+            _ => rng.range(59..194).to_string(),
+        },
+        ("hcl", invalid) => {
+            let digits: String = (0..6)
+                .map(|_| *rng.pick(b"0123456789abcdef") as char)
+                .collect();
+
+            match invalid {
+                false => format!("#{}", digits),
+                true if rng.chance(50) => digits,
+                true => format!("#{}z", &digits[1..]),
+            }
+        }
+        ("ecl", false) => rng
+            .pick(&["amb", "blu", "brn", "gry", "grn", "hzl", "oth"])
+            .to_string(),
+        ("ecl", true) => rng.pick(&["wat", "red", "xry", "zzz"]).to_string(),
+        (_, invalid) => {
+            let length = match invalid {
+                false => 9,
+                true => *rng.pick(&[8, 10]),
+            };
+
+            (0..length).map(|_| rng.range(0..10).to_string()).collect()
+        }
+    }
+}
+
+/// `size` boarding passes with consecutive seat ids, one of which is missing.
+pub fn day5(seed: u64, size: usize) -> Synthetic {
+    let mut rng = Rng::new(seed);
+    let size = size.clamp(2, 1022) as u64;
+
+    let first = rng.range(0..1023 - size);
+    let last = first + size;
+    let missing = rng.range(first + 1..last);
+    let mut ids: Vec<u64> = (first..=last).filter(|id| *id != missing).collect();
+    rng.shuffle(&mut ids);
+
+    let encode = |id: u64| -> String {
+        (0..10)
+            .rev()
+            .map(|bit| match (bit, id >> bit & 1) {
+                (3..=9, 0) => 'F',
+                (3..=9, _) => 'B',
+                (_, 0) => 'L',
+                _ => 'R',
+            })
+            .collect()
+    };
+
+    Synthetic {
+        input: join_lines(ids.iter().map(|id| encode(*id))),
+        part1: last.into(),
+        part2: missing.into(),
+    }
+}
+
+/// `size` groups of up to five persons.
+pub fn day6(seed: u64, size: usize) -> Synthetic {
+    let mut rng = Rng::new(seed);
+    let (mut anyone, mut everyone) = (0, 0);
+
+    let groups: Vec<String> = (0..size)
+        .map(|_| {
+            let persons: Vec<Vec<u8>> = (0..rng.range(1..6))
+                .map(|_| {
+                    let mut questions: Vec<u8> = (b'a'..=b'z').collect();
+                    rng.shuffle(&mut questions);
+                    questions.truncate(rng.range(1..10) as usize);
+                    questions
+                })
+                .collect();
+
+            let union: BTreeSet<u8> = persons.iter().flatten().copied().collect();
+            anyone += union.len();
+            everyone += union
+                .iter()
+                .filter(|question| persons.iter().all(|person| person.contains(question)))
+                .count();
+
+            join_lines(persons.iter().map(|person| String::from_utf8_lossy(person)))
+        })
+        .collect();
+
+    Synthetic {
+        input: groups.join("\n\n"),
+        part1: anyone.into(),
+        part2: everyone.into(),
+    }
+}
+
+/// acyclic rules for `size` colors (at least 12), arranged in six levels. Bags only contain bags
+/// of deeper levels, shiny gold bags are part of the middle level.
+pub fn day7(seed: u64, size: usize) -> Synthetic {
+    const LEVELS: usize = 6;
+    const ADJECTIVES: [&str; 12] = [
+        "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "muted",
+        "pale", "vibrant",
+    ];
+    const COLORS: [&str; 12] = [
+        "aqua", "beige", "black", "blue", "coral", "crimson", "gold", "green", "indigo", "lime",
+        "olive", "red",
+    ];
+
+    let mut rng = Rng::new(seed);
+    let size = size.max(2 * LEVELS);
+
+    let mut names: Vec<String> = (0..size - 1)
+        .map(|index| {
+            let adjective = ADJECTIVES[index % ADJECTIVES.len()];
+            let color = COLORS[index / ADJECTIVES.len() % COLORS.len()];
+
+            match index / (ADJECTIVES.len() * COLORS.len()) {
+                0 => format!("{} {}", adjective, color),
+                n => format!("{}{} {}", adjective, n, color),
+            }
+        })
+        .collect();
+    rng.shuffle(&mut names);
+
+    // level of every color, colors are sorted by level
+    let level_of = |index: usize| index * LEVELS / size;
+    let gold = (LEVELS / 2 * size).div_ceil(LEVELS);
+    names.insert(gold, "shiny gold".to_string());
+
+    let contents: Vec<Vec<(usize, u64)>> = (0..size)
+        .map(|outer| {
+            let deeper = (outer + 1..size)
+                .find(|inner| level_of(*inner) > level_of(outer))
+                .unwrap_or(size);
+
+            if deeper == size {
+                return Vec::new();
+            }
+
+            let mut inner: Vec<usize> = (0..rng.range(0..4))
+                .map(|_| rng.range(deeper as u64..size as u64) as usize)
+                .collect();
+            inner.sort_unstable();
+            inner.dedup();
+
+            inner
+                .into_iter()
+                .map(|inner| (inner, rng.range(1..5)))
+                .collect()
+        })
+        .collect();
+
+    // colors are ordered by level, so every color only depends on colors with a higher index
+    let mut contains_gold = vec![false; size];
+    let mut bags_inside = vec![0; size];
+    for outer in (0..size).rev() {
+        contains_gold[outer] = contents[outer]
+            .iter()
+            .any(|(inner, _)| *inner == gold || contains_gold[*inner]);
+        bags_inside[outer] = contents[outer]
+            .iter()
+            .map(|(inner, count)| count * (1 + bags_inside[*inner]))
+            .sum::<u64>();
+    }
+
+    let mut rules: Vec<String> = (0..size)
+        .map(|outer| {
+            let contents = match contents[outer].as_slice() {
+                [] => "no other bags".to_string(),
+                contents => contents
+                    .iter()
+                    .map(|(inner, count)| {
+                        let bags = if *count == 1 { "bag" } else { "bags" };
+                        format!("{} {} {}", count, names[*inner], bags)
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            };
+
+            format!("{} bags contain {}.", names[outer], contents)
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+
+    Synthetic {
+        input: join_lines(rules),
+        part1: contains_gold
+            .iter()
+            .filter(|contains| **contains)
+            .count()
+            .into(),
+        part2: bags_inside[gold].into(),
+    }
+}
+
+/// program of about `size` instructions in which exactly one `jmp` or `nop` is corrupted.
+///
+/// The program runs straight through, jumping over dead regions. Every dead region starts with
+/// two traps jumping back to the start, every `nop` points to a trap. Corrupting one of the
+/// `jmp` or `nop` instructions leads into a trap, and there's no other single change avoiding it.
+pub fn day8(seed: u64, size: usize) -> Synthetic {
+    #[derive(Copy, Clone)]
+    enum Op {
+        Acc,
+        Jmp,
+        Nop,
+    }
+
+    let mut rng = Rng::new(seed);
+    let mut program: Vec<(Op, i64)> = Vec::with_capacity(size + 4);
+    let (mut path_jumps, mut path_nops, mut traps) = (Vec::new(), Vec::new(), Vec::new());
+
+    while program.len() + 1 < size || traps.is_empty() || path_jumps.len() + path_nops.len() < 2 {
+        match rng.below(10) {
+            0..=5 => program.push((Op::Acc, rng.range(0..101) as i64 - 50)),
+            6 => {
+                path_nops.push(program.len());
+                program.push((Op::Nop, 0));
+            }
+            _ => {
+                let dead = rng.range(2..5) as i64;
+                path_jumps.push(program.len());
+                program.push((Op::Jmp, dead + 1));
+
+                traps.push(program.len());
+                for index in 0..dead {
+                    let position = program.len() as i64;
+                    program.push(match index {
+                        0 | 1 => (Op::Jmp, -position),
+                        _ => (
+                            *rng.pick(&[Op::Acc, Op::Jmp, Op::Nop]),
+                            rng.range(0..21) as i64 - 10,
+                        ),
+                    });
+                }
+            }
+        }
+    }
+    program.push((Op::Acc, rng.range(0..101) as i64 - 50));
+
+    for nop in &path_nops {
+        let trap = *rng.pick(&traps);
+        program[*nop].1 = trap as i64 - *nop as i64;
+    }
+
+    let corrupted = match rng.below(path_jumps.len() + path_nops.len()) {
+        index if index < path_jumps.len() => path_jumps[index],
+        index => path_nops[index - path_jumps.len()],
+    };
+
+    // the fixed program executes every instruction on the path exactly once, the corrupted one
+    // executes the path up to the corrupted instruction before it jumps back to the start
+    let on_path = |index: usize| {
+        !traps
+            .iter()
+            .any(|trap| (*trap..*trap + program[*trap - 1].1 as usize - 1).contains(&index))
+    };
+    let acc_until = |end: usize| -> i64 {
+        (0..end)
+            .filter(|index| on_path(*index))
+            .filter_map(|index| match program[index] {
+                (Op::Acc, value) => Some(value),
+                _ => None,
+            })
+            .sum()
+    };
+    let (part1, part2) = (acc_until(corrupted), acc_until(program.len()));
+
+    program[corrupted].0 = match program[corrupted].0 {
+        Op::Jmp => Op::Nop,
+        _ => Op::Jmp,
+    };
+
+    Synthetic {
+        input: join_lines(program.iter().map(|(op, value)| {
+            let op = match op {
+                Op::Acc => "acc",
+                Op::Jmp => "jmp",
+                Op::Nop => "nop",
+            };
+            format!("{} {:+}", op, value)
+        })),
+        part1: part1.into(),
+        part2: part2.into(),
+    }
+}
+
+/// stream of `size` numbers (between 30 and 1200, larger streams would overflow) with a preamble
+/// of 25 and a single invalid number, which is the sum of a contiguous range of numbers.
+pub fn day9(seed: u64, size: usize) -> Synthetic {
+    const PREAMBLE: usize = 25;
+    let mut rng = Rng::new(seed);
+    let size = size.clamp(PREAMBLE + 5, 1200);
+
+    let mut numbers: Vec<u64> = Vec::with_capacity(size);
+    while numbers.len() < PREAMBLE {
+        let number = rng.range(1..100);
+        if !numbers.contains(&number) {
+            numbers.push(number);
+        }
+    }
+
+    let position = rng.range((size / 2).max(PREAMBLE + 1) as u64..size as u64) as usize;
+    let is_sum = |window: &[u64], number: u64| {
+        window
+            .iter()
+            .enumerate()
+            .any(|(i, a)| window[i + 1..].iter().any(|b| a + b == number))
+    };
+
+    while numbers.len() < size {
+        let window = &numbers[numbers.len() - PREAMBLE..];
+
+        if numbers.len() == position {
+            // sum of an early range, the planted number is the only one equal to it
+            let start = rng.below(position - PREAMBLE);
+            let length = rng.range(2..12).min((position - start) as u64) as usize;
+            let invalid: u64 = numbers[start..start + length].iter().sum();
+
+            if !is_sum(window, invalid) && !numbers.contains(&invalid) {
+                numbers.push(invalid);
+            }
+            continue;
+        }
+
+        // adding up small numbers keeps the stream from growing too fast
+        let mut smallest = window.to_vec();
+        smallest.sort_unstable();
+        smallest.dedup();
+        let a = rng.below(3);
+        let b = (a + 1 + rng.below(2)) % 3;
+
+        numbers.push(smallest[a] + smallest[b]);
+    }
+
+    let invalid = numbers[position];
+    let (start, end) = (0..position)
+        .find_map(|start| {
+            (start + 2..=position)
+                .find(|end| numbers[start..*end].iter().sum::<u64>() == invalid)
+                .map(|end| (start, end))
+        })
+        .expect("invalid number without contiguous range");
+    let range = &numbers[start..end];
+
+    Synthetic {
+        input: join_lines(&numbers),
+        part1: invalid.into(),
+        part2: (range.iter().min().unwrap() + range.iter().max().unwrap()).into(),
+    }
+}
+
+/// `size` adapters with joltage differences of 1 and 3.
+pub fn day10(seed: u64, size: usize) -> Synthetic {
+    let mut rng = Rng::new(seed);
+    let (mut adapters, mut joltage) = (Vec::with_capacity(size), 0);
+    let (mut ones, mut threes, mut arrangements) = (0u64, 1u64, 1u64);
+
+    // arrangements of runs of differences of 1, which are separated by differences of 3
+    let tribonacci = |run: u64| (0..run).fold((1, 0, 0), |(a, b, c), _| (a + b + c, a, b)).0;
+
+    while adapters.len() < size {
+        let mut run = rng.range(1..5).min((size - adapters.len()) as u64);
+        while arrangements.checked_mul(tribonacci(run)).is_none() {
+            run -= 1;
+        }
+
+        for _ in 0..run {
+            joltage += 1;
+            adapters.push(joltage);
+        }
+        ones += run;
+        arrangements *= tribonacci(run);
+
+        if adapters.len() < size {
+            joltage += 3;
+            adapters.push(joltage);
+            threes += 1;
+        }
+    }
+    rng.shuffle(&mut adapters);
+
+    Synthetic {
+        input: join_lines(adapters),
+        part1: (ones * threes).into(),
+        part2: arrangements.into(),
+    }
+}
+
+/// seat layout of `size` by `size` tiles, with a floor tile for every seven seats on average.
+///
+/// The answers are computed by simulating the layout, there is no way to plant them. Unlike the
+/// puzzle inputs random layouts don't always become stable, some of the seats that keep changing
+/// are replaced with floor until the layout does.
+pub fn day11(seed: u64, size: usize) -> Synthetic {
+    let mut rng = Rng::new(seed);
+    let cells = (0..size * size)
+        .map(|_| match rng.chance(12) {
+            true => State::Blocked,
+            false => State::Empty,
+        })
+        .collect();
+    let mut seats = Grid::new(size, cells);
+
+    let (part1, part2) = loop {
+        let result =
+            settle(Automaton::new(seats.clone(), &Adjacent, Life::seats(4))).and_then(|part1| {
+                settle(Automaton::new(seats.clone(), &Visible, Life::seats(5)))
+                    .map(|part2| (part1, part2))
+            });
+
+        match result {
+            Ok(answers) => break answers,
+            Err(mut oscillating) => {
+                // replacing a few of them is usually enough and keeps the layout dense
+                rng.shuffle(&mut oscillating);
+                oscillating.truncate(oscillating.len() / 10 + 1);
+
+                for index in oscillating {
+                    seats.cells_mut()[index] = State::Blocked;
+                }
+            }
+        }
+    };
+
+    Synthetic {
+        input: join_lines(seats.to_string().lines()),
+        part1: part1.into(),
+        part2: part2.into(),
+    }
+}
+
+/// number of occupied seats once the layout is stable, or the indices of the seats that keep
+/// changing if it never becomes stable.
+fn settle(mut seats: Automaton<State, Life>) -> Result<usize, Vec<usize>> {
+    // the states of a layout are finite, so it becomes stable or repeats before the limit
+    match seats.run(usize::MAX) {
+        Outcome::Stable(_) => Ok(seats.count_alive()),
+        Outcome::Cycle { period, .. } => {
+            let mut changing = BTreeSet::new();
+            for _ in 0..period {
+                let current = seats.grid().clone();
+                seats.step();
+                changing.extend(
+                    current
+                        .cells()
+                        .iter()
+                        .zip(seats.grid().cells())
+                        .enumerate()
+                        .filter(|(_, (current, next))| current != next)
+                        .map(|(index, _)| index),
+                );
+            }
+
+            Err(changing.into_iter().collect())
+        }
+        Outcome::Limit => unreachable!("the seats are simulated without a limit"),
+    }
+}
+
+fn join_lines<T: ToString>(lines: impl IntoIterator<Item = T>) -> String {
+    lines
+        .into_iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
@@ -0,0 +1,21 @@
+use advent_of_code_2020::answers::{self, Answers};
+use advent_of_code_2020::{runner, Registry};
+use std::fs;
+
+/// every solver still produces the checked-in answer for the checked-in input
+#[test]
+fn test_known_answers() {
+    let known = Answers::load(answers::DEFAULT_PATH).unwrap();
+
+    let failures: Vec<String> = Registry::default()
+        .runners()
+        .map(|runner| {
+            let input = fs::read_to_string(runner::default_input_path(runner.day())).unwrap();
+            answers::verify(&known, runner, &input)
+        })
+        .filter(|verification| !verification.is_correct())
+        .map(|verification| verification.to_string())
+        .collect();
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
@@ -0,0 +1,458 @@
+mod common;
+
+use common::Stub;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+fn aoc(args: &[&str], stdin: &str) -> Output {
+    aoc_in(Path::new("."), args, stdin)
+}
+
+fn aoc_in(dir: &Path, args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_advent-of-code-2020"))
+        .current_dir(dir)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // commands rejecting their arguments exit without reading the input
+    if let Err(e) = child.stdin.take().unwrap().write_all(stdin.as_bytes()) {
+        assert_eq!(std::io::ErrorKind::BrokenPipe, e.kind());
+    }
+
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_run_reads_stdin() {
+    let output = aoc(
+        &["run", "--day", "1", "--part", "1", "--input", "-"],
+        "1721\n979\n366\n299\n675\n1456\n",
+    );
+
+    assert!(output.status.success());
+    assert_eq!("514579\n", String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_verify_reports_mismatch() {
+    let output = aoc(
+        &[
+            "verify", "--day", "1", "--part", "1", "--input", "-", "--expect", "1",
+        ],
+        "1721\n979\n366\n299\n675\n1456\n",
+    );
+
+    assert_eq!(Some(2), output.status.code());
+}
+
+#[test]
+fn test_io_errors_name_the_action() {
+    let output = aoc(
+        &["run", "--day", "1", "--part", "1", "--input", "missing.txt"],
+        "",
+    );
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to read missing.txt"));
+
+    let output = aoc(
+        &[
+            "verify",
+            "--day",
+            "1",
+            "--part",
+            "1",
+            "--input",
+            "-",
+            "--answers",
+            "missing/answers.txt",
+            "--record",
+        ],
+        "1721\n979\n366\n299\n675\n1456\n",
+    );
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to write missing/answers.txt"));
+}
+
+#[test]
+fn test_parse_errors_fail_with_report() {
+    let output = aoc(
+        &["run", "--day", "8", "--part", "1", "--input", "-"],
+        "jpm +1\n",
+    );
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("day 8, line 1, column 1"));
+}
+
+#[test]
+fn test_unknown_puzzle() {
+    let output = aoc(&["run", "--day", "26", "--part", "1"], "");
+
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[test]
+fn test_generated_input_solves_to_printed_answers() {
+    let generated = aoc(&["generate", "--day", "9", "--seed", "3"], "");
+    assert!(generated.status.success());
+
+    let answers = String::from_utf8(generated.stderr).unwrap();
+    let input = String::from_utf8(generated.stdout).unwrap();
+    let solved = aoc(
+        &["run", "--day", "9", "--part", "2", "--input", "-"],
+        &input,
+    );
+
+    assert!(answers.ends_with(&format!(
+        "part 2: {}",
+        String::from_utf8(solved.stdout).unwrap()
+    )));
+}
+
+#[test]
+fn test_batch_runs_every_input_of_a_day() {
+    let dir = std::env::temp_dir().join(format!("aoc-batch-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("day1")).unwrap();
+    std::fs::write(
+        dir.join("day1/alice.txt"),
+        "1721\n979\n366\n299\n675\n1456\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("day1/bob.txt"), "1000\n1020\n7\n").unwrap();
+    std::fs::write(dir.join("day1/carol.txt"), "1\n2\n").unwrap();
+
+    let output = aoc(&["batch", "--dir", dir.to_str().unwrap()], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(stdout.contains("alice.txt"));
+    assert!(stdout.contains("514579"));
+    assert!(stdout.contains("1020000"));
+    assert!(stdout
+        .lines()
+        .any(|line| line.starts_with("carol.txt") && line.contains("panicked")));
+}
+
+#[test]
+fn test_params_override_the_puzzle_constants() {
+    let example = include_str!("../fixtures/2020/day9/example.txt");
+    let config = std::env::temp_dir().join(format!("aoc-params-{}.txt", std::process::id()));
+    std::fs::write(&config, "# the example\n9.preamble = 5\n").unwrap();
+    let config = config.to_str().unwrap();
+
+    let output = aoc(
+        &[
+            "run",
+            "--day",
+            "9",
+            "--part",
+            "1",
+            "--input",
+            "-",
+            "--param",
+            "9.preamble=5",
+        ],
+        example,
+    );
+    assert!(output.status.success());
+    assert_eq!("127\n", String::from_utf8_lossy(&output.stdout));
+
+    let output = aoc(
+        &[
+            "--config", config, "run", "--day", "9", "--part", "2", "--input", "-",
+        ],
+        example,
+    );
+    assert!(output.status.success());
+    assert_eq!("62\n", String::from_utf8_lossy(&output.stdout));
+
+    let output = aoc(
+        &[
+            "params",
+            "--day",
+            "9",
+            "--config",
+            config,
+            "--param",
+            "9.preamble=7",
+        ],
+        "",
+    );
+    std::fs::remove_file(config).unwrap();
+    assert_eq!("9.preamble = 7\n", String::from_utf8_lossy(&output.stdout));
+
+    let output = aoc(&["params", "--param", "9.size=5"], "");
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown parameter `size`"));
+}
+
+#[test]
+fn test_run_all_reports_every_day() {
+    let dir = std::env::temp_dir().join(format!("aoc-run-all-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("input/2020")).unwrap();
+    std::fs::write(dir.join("input/2020/day1.txt"), "1\n2\n").unwrap();
+    std::fs::write(dir.join("input/2020/day5.txt"), "FBFBBFFRLR\nFFFFFFFLLL\n").unwrap();
+
+    let output = aoc_in(&dir, &["run-all", "--threads", "4"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(stdout.contains("day  1 part 1: panicked   no solution found"));
+    assert!(stdout.contains("day  5 part 1: unknown    357"));
+    assert!(stdout.contains("day  7 part 1: failed"));
+    assert!(stdout.contains("  1  panicked   panicked\n"));
+    assert!(stdout.contains("0 ok, 2 unknown, 0 wrong, 18 failed, 2 panicked"));
+}
+
+#[test]
+fn test_run_all_cancels_slow_parts() {
+    let dir = std::env::temp_dir().join(format!("aoc-timeout-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("input/2020")).unwrap();
+    std::fs::copy("input/2020/day11.txt", dir.join("input/2020/day11.txt")).unwrap();
+
+    let output = aoc_in(&dir, &["run-all", "--timeout", "0.001"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(stdout.contains("day 11 part 1: timed out  cancelled after 1ms"));
+    assert!(stdout.contains(" 11  timed out  timed out\n"));
+}
+
+#[test]
+fn test_fetch_downloads_missing_inputs() {
+    let dir = std::env::temp_dir().join(format!("aoc-fetch-cli-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let stub = Stub::serve(vec![(200, "1721\n979\n"), (400, "log in")]);
+    let fetch = |day| {
+        let dir = dir.to_str().unwrap();
+        let args = [
+            "fetch",
+            "--day",
+            day,
+            "--dir",
+            dir,
+            "--base-url",
+            &stub.url,
+            "--session",
+            "s",
+        ];
+
+        aoc(&args, "")
+    };
+
+    let output = fetch("1");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("day  1: downloaded to "));
+
+    let output = fetch("1");
+    assert_eq!("day  1: cached\n", String::from_utf8_lossy(&output.stdout));
+
+    let output = fetch("2");
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .starts_with("error: failed to fetch the input of day 2: the session token was rejected"));
+
+    assert_eq!(2, stub.requests().len());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_submit_records_attempts_and_refuses_known_wrong_answers() {
+    let history = std::env::temp_dir().join(format!("aoc-submit-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&history);
+    let stub = Stub::serve(vec![
+        (200, include_str!("pages/too_high.html")),
+        (200, include_str!("pages/correct.html")),
+    ]);
+    let submit = |part, history: &Path| {
+        let args = [
+            "submit",
+            "--day",
+            "1",
+            "--part",
+            part,
+            "--input",
+            "-",
+            "--base-url",
+            &stub.url,
+            "--session",
+            "s",
+            "--history",
+            history.to_str().unwrap(),
+        ];
+
+        aoc(&args, "1721\n979\n366\n299\n675\n1456\n")
+    };
+
+    let output = submit("1", &history);
+    assert_eq!(Some(2), output.status.code());
+    assert_eq!(
+        "day  1 part 1: 514579 too-high, wait 60s before the next submission\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let requests = stub.requests();
+    assert_eq!("POST /2020/day/1/answer", requests[0].line);
+    assert_eq!("level=1&answer=514579", requests[0].body);
+    assert_eq!(Some("session=s"), requests[0].header("cookie"));
+
+    // known to be wrong, so the server isn't asked again
+    let output = submit("1", &history);
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!(
+        "error: not submitted: wrong answer, 514579 was already too high\n",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // part 2 is a different answer, but the server asked to wait
+    let output = submit("2", &history);
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .starts_with("error: not submitted: the server asked to wait, try again in "));
+    assert_eq!(1, stub.requests().len());
+
+    // once the cooldown is over
+    let recorded = std::fs::read_to_string(&history).unwrap();
+    let time: u64 = recorded
+        .lines()
+        .nth(1)
+        .unwrap()
+        .split(' ')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let earlier = recorded.replace(&time.to_string(), &(time - 60).to_string());
+    std::fs::write(&history, earlier).unwrap();
+
+    let output = submit("2", &history);
+    assert!(output.status.success());
+    assert_eq!(
+        "day  1 part 2: 241861950 correct\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_eq!("level=2&answer=241861950", stub.requests()[1].body);
+
+    let output = submit("2", &history);
+    assert_eq!(
+        "error: not submitted: already solved, the answer is 241861950\n",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    std::fs::remove_file(history).unwrap();
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_export_csv() {
+    let output = aoc(
+        &["export", "--day", "8", "--format", "csv", "--input", "-"],
+        "nop +0\nacc -1\n",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        "operation,argument\nnop,0\nacc,-1\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+#[cfg(not(feature = "serde"))]
+fn test_export_requires_serde() {
+    let output = aoc(&["export", "--day", "8", "--input", "-"], "nop +0\n");
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--features serde"));
+}
+
+#[test]
+fn test_render_writes_a_frame_per_slope() {
+    let dir = std::env::temp_dir().join(format!("aoc-render-{}", std::process::id()));
+    let output = aoc(
+        &[
+            "--param",
+            "3.slopes=1,1 3,1",
+            "render",
+            "--day",
+            "3",
+            "--part",
+            "2",
+            "--input",
+            "-",
+            "--scale",
+            "1",
+            "--output",
+            dir.to_str().unwrap(),
+        ],
+        "..#\n#..\n.#.\n",
+    );
+    let first = std::fs::read(dir.join("frame-0000.ppm"));
+    let frames = std::fs::read_dir(&dir).map(Iterator::count);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(2, frames.unwrap());
+    // the path of 3,1 reaches the 7th column, so the map is repeated 3 times
+    assert!(first.unwrap().starts_with(b"P6\n9 3\n255\n"));
+}
+
+#[test]
+fn test_render_normalizes_input() {
+    let dir = std::env::temp_dir().join(format!("aoc-render-normalize-{}", std::process::id()));
+    let output = aoc(
+        &[
+            "render",
+            "--day",
+            "11",
+            "--part",
+            "1",
+            "--input",
+            "-",
+            "--scale",
+            "1",
+            "--output",
+            dir.to_str().unwrap(),
+        ],
+        "\u{feff}L.L  \r\nLLL\r\n\r\n",
+    );
+    let first = std::fs::read(dir.join("frame-0000.ppm"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert!(first.unwrap().starts_with(b"P6\n3 2\n255\n"));
+}
+
+#[test]
+#[cfg(not(feature = "images"))]
+fn test_render_gif_requires_images() {
+    let output = aoc(
+        &[
+            "render",
+            "--day",
+            "11",
+            "--part",
+            "1",
+            "--input",
+            "-",
+            "--format",
+            "gif",
+            "--output",
+            "seats.gif",
+        ],
+        "L.L\n",
+    );
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--features images"));
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use advent_of_code_2020::input::{Cache, Client, FetchError};
+use common::Stub;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    dir
+}
+
+#[test]
+fn test_downloads_once_and_caches() {
+    let stub = Stub::serve(vec![(200, "1721\n979\n")]);
+    let client = Client::new(&stub.url, Some("secret".to_string()));
+    let dir = cache_dir("cache");
+    let cache = Cache::new(&dir);
+
+    assert_eq!("1721\n979\n", cache.get(&client, 1).unwrap());
+    assert_eq!("1721\n979\n", cache.get(&client, 1).unwrap());
+    assert_eq!(
+        "1721\n979\n",
+        fs::read_to_string(dir.join("day1.txt")).unwrap()
+    );
+
+    let requests = stub.requests();
+    assert_eq!(1, requests.len());
+    assert_eq!("GET /2020/day/1/input", requests[0].line);
+    assert_eq!(Some("session=secret"), requests[0].header("cookie"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cached_inputs_need_no_session() {
+    let dir = cache_dir("offline");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("day3.txt"), "..#\n").unwrap();
+    // nothing listens on the base url, any request would fail
+    let client = Client::new("http://127.0.0.1:1", None);
+    let cache = Cache::new(&dir);
+
+    assert_eq!("..#\n", cache.get(&client, 3).unwrap());
+    assert!(matches!(cache.get(&client, 4), Err(FetchError::NoSession)));
+    assert!(!dir.join("day4.txt").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_expired_session() {
+    let stub = Stub::serve(vec![(
+        400,
+        "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+    )]);
+    let client = Client::new(&stub.url, Some("expired".to_string()));
+
+    let error = client.download(1).unwrap_err();
+
+    assert!(matches!(error, FetchError::InvalidSession));
+    assert!(error.to_string().contains("expired"));
+}
+
+#[test]
+fn test_locked_day() {
+    let stub = Stub::serve(vec![(
+        404,
+        "Please don't repeatedly request this endpoint before it unlocks!",
+    )]);
+    let client = Client::new(&format!("{}/", stub.url), Some("secret".to_string()));
+    let dir = cache_dir("locked");
+    let cache = Cache::new(&dir);
+
+    let error = cache.get(&client, 25).unwrap_err();
+
+    assert!(matches!(error, FetchError::NotUnlocked { day: 25 }));
+    assert_eq!(
+        "day 25 doesn't exist or isn't unlocked yet",
+        error.to_string()
+    );
+    assert!(!cache.contains(25));
+}
+
+#[test]
+fn test_unexpected_status() {
+    let stub = Stub::serve(vec![(500, "Internal Server Error\n")]);
+    let client = Client::new(&stub.url, Some("secret".to_string()));
+
+    assert_eq!(
+        "unexpected response 500: Internal Server Error",
+        client.download(1).unwrap_err().to_string()
+    );
+}
@@ -0,0 +1,50 @@
+use advent_of_code_2020::{runner, Registry};
+use std::fs;
+
+/// the puzzle input as it looks after saving it on Windows, with a byte order mark, CRLF line
+/// endings, trailing whitespace and a trailing blank line
+fn windows(input: &str) -> String {
+    let lines: Vec<String> = input.lines().map(|line| format!("{} ", line)).collect();
+
+    format!("\u{feff}{}\r\n\r\n", lines.join("\r\n"))
+}
+
+fn assert_same_answers(day: u32) {
+    let registry = Registry::default();
+    let input = fs::read_to_string(runner::default_input_path(day)).unwrap();
+
+    for runner in registry.day(day) {
+        assert_eq!(
+            runner.solve(&input).unwrap(),
+            runner.solve(&windows(&input)).unwrap(),
+            "day {} part {}",
+            day,
+            runner.part()
+        );
+    }
+}
+
+macro_rules! crlf_tests {
+    ($($name:ident: $day:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                assert_same_answers($day);
+            }
+        )*
+    };
+}
+
+crlf_tests! {
+    test_day1_crlf: 1,
+    test_day2_crlf: 2,
+    test_day3_crlf: 3,
+    test_day4_crlf: 4,
+    test_day5_crlf: 5,
+    test_day6_crlf: 6,
+    test_day7_crlf: 7,
+    test_day8_crlf: 8,
+    test_day9_crlf: 9,
+    test_day10_crlf: 10,
+    test_day11_crlf: 11,
+}
@@ -0,0 +1,58 @@
+use advent_of_code_2020::submit::{Feedback, Reply};
+use std::time::Duration;
+
+fn reply(page: &str) -> (Feedback, Option<Duration>) {
+    let reply = Reply::parse(page);
+
+    (reply.feedback, reply.wait)
+}
+
+#[test]
+fn test_recorded_pages() {
+    let minutes = |minutes: u64| Some(Duration::from_secs(60 * minutes));
+
+    assert_eq!(
+        (Feedback::Correct, None),
+        reply(include_str!("pages/correct.html"))
+    );
+    assert_eq!(
+        (Feedback::TooHigh, minutes(1)),
+        reply(include_str!("pages/too_high.html"))
+    );
+    assert_eq!(
+        (Feedback::TooLow, minutes(1)),
+        reply(include_str!("pages/too_low.html"))
+    );
+    assert_eq!(
+        (Feedback::Wrong, minutes(5)),
+        reply(include_str!("pages/wrong.html"))
+    );
+    assert_eq!(
+        (Feedback::TooSoon, Some(Duration::from_secs(134))),
+        reply(include_str!("pages/too_soon.html"))
+    );
+    assert_eq!(
+        (Feedback::Solved, None),
+        reply(include_str!("pages/solved.html"))
+    );
+}
+
+#[test]
+fn test_message_is_the_text_of_the_article() {
+    let reply = Reply::parse(include_str!("pages/correct.html"));
+
+    assert_eq!(
+        "That's the right answer! You are one gold star closer to saving your vacation. \
+         [Continue to Part Two]",
+        reply.message
+    );
+}
+
+#[test]
+fn test_unknown_pages() {
+    assert_eq!(
+        (Feedback::Unrecognized, None),
+        reply("<html>maintenance</html>")
+    );
+    assert_eq!((Feedback::Unrecognized, None), reply(""));
+}
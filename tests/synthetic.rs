@@ -0,0 +1,45 @@
+use advent_of_code_2020::{synth, Registry};
+
+const SEEDS: [u64; 4] = [0, 1, 2020, 0xdead_beef];
+
+#[test]
+fn test_synthetic_inputs() {
+    let registry = Registry::default();
+
+    for runner in registry.runners() {
+        // the seat simulation of day 11 is too slow for large layouts in debug builds
+        let sizes = match runner.day() {
+            11 => [10, 30],
+            _ => [30, 200],
+        };
+
+        for seed in SEEDS.iter().copied() {
+            for size in sizes.iter().copied() {
+                let synthetic = synth::generate(runner.day(), seed, size).unwrap();
+                let expected = match runner.part() {
+                    1 => &synthetic.part1,
+                    _ => &synthetic.part2,
+                };
+
+                assert_eq!(
+                    Ok(expected.clone()),
+                    runner.solve(&synthetic.input).map_err(|e| e.to_string()),
+                    "day {} part {} seed {} size {}",
+                    runner.day(),
+                    runner.part(),
+                    seed,
+                    size
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generators_are_deterministic() {
+    for day in 1..=11 {
+        assert_eq!(synth::generate(day, 7, 50), synth::generate(day, 7, 50));
+        assert_ne!(synth::generate(day, 7, 50), synth::generate(day, 8, 50));
+    }
+    assert_eq!(None, synth::generate(12, 7, 50));
+}